@@ -0,0 +1,93 @@
+//! Generates one test per day, input and expected part from the `inputs/` tree, for
+//! `tests/inputs.rs` to include. Also tells the crate which cargo profile it's built with.
+
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn sorted_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut dirs = match fs::read_dir(path) {
+        Ok(entries) => entries
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.is_dir())
+            .collect::<Vec<_>>(),
+        Err(_) => vec![],
+    };
+    dirs.sort();
+    dirs
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().unwrap().to_str().unwrap()
+}
+
+/// The name of the profile being built, e.g. `perf`. `PROFILE` only says `debug` or `release`,
+/// so this is taken from the profile's directory in `OUT_DIR`, i.e.
+/// `target/<profile>/build/<package>/out`.
+fn profile_name() -> String {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    out_dir
+        .ancestors()
+        .nth(3)
+        .and_then(|p| p.file_name())
+        .and_then(|p| p.to_str())
+        .map(|p| p.to_owned())
+        .unwrap_or_else(|| env::var("PROFILE").unwrap())
+}
+
+fn main() {
+    println!("cargo:rustc-env=AOC24_PROFILE={}", profile_name());
+    let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+    println!("cargo:rerun-if-changed={}", inputs.display());
+
+    let mut tests = String::new();
+    for day_dir in sorted_dirs(&inputs) {
+        let day = file_name(&day_dir);
+        for case_dir in sorted_dirs(&day_dir) {
+            let case = file_name(&case_dir);
+            if !case_dir.join("input.txt").is_file() {
+                continue;
+            }
+            let ident = format!("{day}_{case}").replace(|c: char| !c.is_alphanumeric(), "_");
+            let cell = ident.to_uppercase();
+
+            // A case directory can opt out of testing with an `ignore.txt` giving the reason,
+            // e.g. for solvers that take too long on that input, or opt out of testing one part
+            // with an `ignore-part1.txt` or `ignore-part2.txt`.
+            let ignore_file = |name: &str| match fs::read_to_string(case_dir.join(name)) {
+                Ok(reason) => format!("#[ignore = {:?}]\n", reason.trim()),
+                Err(_) => String::new(),
+            };
+            let case_ignore = ignore_file("ignore.txt");
+
+            let mut case_tests = String::new();
+            for part in 1..=2 {
+                let ignore = match case_ignore.is_empty() {
+                    true => ignore_file(&format!("ignore-part{part}.txt")),
+                    false => case_ignore.clone(),
+                };
+                if case_dir.join(format!("part{part}.txt")).is_file() {
+                    write!(
+                        case_tests,
+                        "#[test]\n{ignore}fn {ident}_part{part}() {{\n    check(&{cell}, {day:?}, {case:?}, {part});\n}}\n\n"
+                    )
+                    .unwrap();
+                }
+            }
+            if !case_tests.is_empty() {
+                writeln!(
+                    tests,
+                    "static {cell}: OnceLock<Solution> = OnceLock::new();\n"
+                )
+                .unwrap();
+                tests.push_str(&case_tests);
+            }
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("input_tests.rs");
+    fs::write(out, tests).unwrap();
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use crate::point::Point;
+
+/// A solver's answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// An `x,y` position, e.g. day18's first blocking byte.
+    Coord(isize, isize),
+    /// A comma separated list, e.g. day23's password or day24's swapped wires.
+    List(Vec<String>),
+}
+
+impl Answer {
+    /// Whether `expected`, as read from an expectation file, is the same answer. Surrounding
+    /// whitespace such as a trailing newline is ignored, as is whitespace around list commas.
+    pub fn matches(&self, expected: &str) -> bool {
+        let expected = expected.trim();
+        match self {
+            Answer::Int(v) => expected.parse::<i128>() == Ok(*v),
+            Answer::Text(s) => s.trim() == expected,
+            Answer::Coord(x, y) => match expected.split_once(',') {
+                Some((ex, ey)) => {
+                    ex.trim().parse::<isize>() == Ok(*x) && ey.trim().parse::<isize>() == Ok(*y)
+                }
+                None => false,
+            },
+            Answer::List(items) => {
+                if items.is_empty() {
+                    return expected.is_empty();
+                }
+                let expected = expected.split(',').map(|e| e.trim()).collect::<Vec<_>>();
+                items.len() == expected.len()
+                    && items
+                        .iter()
+                        .zip(expected.iter())
+                        .all(|(a, e)| a.trim() == *e)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{v}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            Answer::Coord(x, y) => write!(f, "{x},{y}"),
+            Answer::List(items) => write!(f, "{}", items.join(",")),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Answer {
+                    Answer::Int(v as i128)
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i32, i64, isize, u8, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_owned())
+    }
+}
+
+impl From<(isize, isize)> for Answer {
+    fn from((x, y): (isize, isize)) -> Answer {
+        Answer::Coord(x, y)
+    }
+}
+
+impl From<Point> for Answer {
+    fn from(p: Point) -> Answer {
+        Answer::Coord(p.x, p.y)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(items: Vec<String>) -> Answer {
+        Answer::List(items)
+    }
+}
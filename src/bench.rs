@@ -0,0 +1,220 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::{Error, Failure},
+    harness::Case,
+};
+
+/// How much measuring to do for each input.
+#[derive(Debug, Clone, Copy)]
+pub enum Budget {
+    Iters(usize),
+    Time(Duration),
+}
+
+/// Runs `case` untimed `warmup` times, then repeatedly until `budget` is spent. Each run is
+/// limited to `timeout`, if given. Gives up with the failures of the first run that fails with
+/// an error, crashes or times out, since its timing wouldn't mean anything. Wrong answers don't
+/// count against a run.
+pub fn measure(
+    case: &Case,
+    warmup: usize,
+    budget: Budget,
+    timeout: Option<Duration>,
+) -> Result<Stats, Vec<Failure>> {
+    let run = || {
+        let report = match timeout {
+            Some(timeout) => case.run_with_timeout(timeout),
+            None => case.run(),
+        };
+        let stopped = report
+            .failures()
+            .into_iter()
+            .filter(|f| {
+                matches!(
+                    f,
+                    Failure::Solver { .. } | Failure::Crash { .. } | Failure::Timeout { .. }
+                )
+            })
+            .collect::<Vec<_>>();
+        match stopped.is_empty() {
+            true => Ok(report.duration),
+            false => Err(stopped),
+        }
+    };
+
+    for _ in 0..warmup {
+        run()?;
+    }
+
+    let mut samples = Vec::<Duration>::new();
+    let start = Instant::now();
+    loop {
+        samples.push(run()?);
+        let done = match budget {
+            Budget::Iters(iters) => samples.len() >= iters,
+            Budget::Time(budget) => start.elapsed() >= budget,
+        };
+        if done {
+            break;
+        }
+    }
+    Ok(Stats::new(&samples))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty());
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        // Nearest-rank percentile.
+        let p95 = sorted[(n * 95).div_ceil(100) - 1];
+
+        let mean_ns = sorted.iter().map(|d| d.as_nanos() as f64).sum::<f64>() / n as f64;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_nanos() as f64 - mean_ns).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Stats {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean_ns as u64),
+            p95,
+            stddev: Duration::from_nanos(variance.sqrt() as u64),
+        }
+    }
+}
+
+/// Median timings from an earlier run, keyed by day and input name. On disk this is one
+/// `day input median_ns` line per entry.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    medians: HashMap<(String, String), Duration>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Baseline, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Couldn't read {}: {e}", path.display())))?;
+        let mut baseline = Baseline::default();
+        for (i, line) in contents.lines().enumerate() {
+            let bad_line = || Error::Io(format!("{}:{}: malformed line", path.display(), i + 1));
+            // Input names may contain spaces, so they're whatever lies between the day and the
+            // nanoseconds.
+            let Some((day, (input, ns))) = line
+                .split_once(' ')
+                .and_then(|(day, rest)| Some((day, rest.rsplit_once(' ')?)))
+            else {
+                return Err(bad_line());
+            };
+            let ns = ns.parse::<u64>().map_err(|_| bad_line())?;
+            baseline.insert(day, input, Duration::from_nanos(ns));
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut entries = self.medians.iter().collect::<Vec<_>>();
+        entries.sort();
+        let contents = entries
+            .iter()
+            .map(|((day, input), median)| format!("{day} {input} {}\n", median.as_nanos()))
+            .collect::<String>();
+        std::fs::write(path, contents)
+            .map_err(|e| Error::Io(format!("Couldn't write {}: {e}", path.display())))
+    }
+
+    pub fn insert(&mut self, day: &str, input: &str, median: Duration) {
+        self.medians
+            .insert((day.to_owned(), input.to_owned()), median);
+    }
+
+    pub fn get(&self, day: &str, input: &str) -> Option<Duration> {
+        self.medians
+            .get(&(day.to_owned(), input.to_owned()))
+            .copied()
+    }
+}
+
+/// Percentage change from `base` to `now`; positive means slower. `None` if `base` is zero, as
+/// no change from nothing is a percentage.
+pub fn change_pct(base: Duration, now: Duration) -> Option<f64> {
+    if base.is_zero() {
+        return None;
+    }
+    Some((now.as_nanos() as f64 - base.as_nanos() as f64) / base.as_nanos() as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_pct_compares_against_base() {
+        let ms = Duration::from_millis;
+        assert_eq!(change_pct(ms(10), ms(15)), Some(50.0));
+        assert_eq!(change_pct(ms(10), ms(5)), Some(-50.0));
+        assert_eq!(change_pct(ms(10), ms(10)), Some(0.0));
+        assert_eq!(change_pct(Duration::ZERO, ms(10)), None);
+        assert_eq!(change_pct(Duration::ZERO, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn baselines_round_trip_names_with_spaces() {
+        let path = std::env::temp_dir().join(format!("aoc24-baseline-{}", std::process::id()));
+        let mut baseline = Baseline::default();
+        baseline.insert("day01", "real", Duration::from_nanos(1234));
+        baseline.insert("day02", "my  big input ", Duration::from_nanos(5678));
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(
+            loaded.get("day01", "real"),
+            Some(Duration::from_nanos(1234))
+        );
+        assert_eq!(
+            loaded.get("day02", "my  big input "),
+            Some(Duration::from_nanos(5678))
+        );
+    }
+
+    #[test]
+    fn malformed_baselines_are_rejected() {
+        let path = std::env::temp_dir().join(format!("aoc24-bad-baseline-{}", std::process::id()));
+        for contents in ["day01 1234\n", "day01 real\n", "day01 real fast\n"] {
+            std::fs::write(&path, contents).unwrap();
+            let loaded = Baseline::load(&path);
+            assert!(
+                matches!(&loaded, Err(Error::Io(e)) if e.ends_with(":1: malformed line")),
+                "{contents:?} gave {:?}",
+                loaded.map(|_| ())
+            );
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
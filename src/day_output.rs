@@ -1,35 +1,273 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answer::Answer,
+    params::{ParamError, Params},
+    parse::ParseError,
+};
+
+#[derive(Clone)]
 pub struct DayOutput {
-    part1: Option<String>,
-    part2: Option<String>,
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+    diagnostics: Vec<(String, String)>,
+    started: Instant,
+    parsed: Option<Instant>,
+    part1_at: Option<Instant>,
+    part2_at: Option<Instant>,
+    cancel: CancelToken,
+    params: Params,
+    progress: Option<Progress>,
+}
+
+/// Lets the harness ask a running solver to stop early, e.g. when it has run out of time.
+/// Solvers see it through `DayOutput::check_cancelled`.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A copy of a solver's output as of its latest answer, diagnostic or parse mark, shared with the
+/// harness. If the harness gives up waiting for a solver, it reports this instead, so parts
+/// answered before the solver stopped aren't lost.
+#[derive(Clone, Default)]
+pub struct Progress(Arc<Mutex<Option<DayOutput>>>);
+
+impl Progress {
+    pub fn latest(&self) -> Option<DayOutput> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, output: &DayOutput) {
+        let mut copy = output.clone();
+        // The copy mustn't hold on to its own `Progress`, or neither would ever be freed.
+        copy.progress = None;
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(copy);
+    }
+}
+
+/// Where a day's time went. Each phase runs from the previous mark (or the start of the day) to
+/// its own, so a solver that reports both parts together books the shared work to part 1.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timings {
+    pub parse: Option<Duration>,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputError {
+    /// A part was answered more than once. The first answer is kept.
+    AlreadyAnswered {
+        part: usize,
+        first: Answer,
+        second: Answer,
+    },
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputError::AlreadyAnswered {
+                part,
+                first,
+                second,
+            } => write!(
+                f,
+                "part {part} was answered twice, first with {first} and then with {second}"
+            ),
+        }
+    }
+}
+
+/// Why a solver stopped before finishing.
+#[derive(Debug, Clone)]
+pub enum DayError {
+    Parse(ParseError),
+    Output(OutputError),
+    Param(ParamError),
+    /// The solver noticed it had been cancelled and gave up.
+    Cancelled,
+}
+
+impl fmt::Display for DayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayError::Parse(e) => write!(f, "{e}"),
+            DayError::Output(e) => write!(f, "{e}"),
+            DayError::Param(e) => write!(f, "{e}"),
+            DayError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl From<ParseError> for DayError {
+    fn from(e: ParseError) -> DayError {
+        DayError::Parse(e)
+    }
+}
+
+impl From<ParamError> for DayError {
+    fn from(e: ParamError) -> DayError {
+        DayError::Param(e)
+    }
+}
+
+impl From<OutputError> for DayError {
+    fn from(e: OutputError) -> DayError {
+        DayError::Output(e)
+    }
+}
+
+impl Default for DayOutput {
+    fn default() -> Self {
+        DayOutput::new()
+    }
 }
 
 impl DayOutput {
     pub fn new() -> DayOutput {
+        DayOutput::with_cancel(CancelToken::default())
+    }
+
+    pub fn with_cancel(cancel: CancelToken) -> DayOutput {
         DayOutput {
             part1: None,
             part2: None,
+            diagnostics: vec![],
+            started: Instant::now(),
+            parsed: None,
+            part1_at: None,
+            part2_at: None,
+            cancel,
+            params: Params::default(),
+            progress: None,
+        }
+    }
+
+    /// Shares a copy of the output with the harness whenever it changes.
+    pub fn with_progress(mut self, progress: Progress) -> DayOutput {
+        progress.record(&self);
+        self.progress = Some(progress);
+        self
+    }
+
+    fn changed(&self) {
+        if let Some(progress) = &self.progress {
+            progress.record(self);
+        }
+    }
+
+    /// Supplies the input's parameters, as read from its `params.toml`.
+    pub fn with_params(mut self, params: Params) -> DayOutput {
+        self.params = params;
+        self
+    }
+
+    /// The input's value for parameter `name`, or `default` if it doesn't set one. Defaults
+    /// should be the values for the real puzzle input, so that it can be used as published.
+    pub fn param<T: FromStr>(&self, name: &str, default: T) -> Result<T, DayError> {
+        Ok(self.params.get(name)?.unwrap_or(default))
+    }
+
+    /// Fails with `DayError::Cancelled` if the harness has asked this solver to stop. Cheap
+    /// enough to call on every iteration of a long-running loop.
+    pub fn check_cancelled(&self) -> Result<(), DayError> {
+        match self.cancel.is_cancelled() {
+            true => Err(DayError::Cancelled),
+            false => Ok(()),
         }
     }
 
-    pub fn part1(&mut self, result: String) {
-        if self.part1.is_some() {
-            panic!()
+    /// Marks the end of input parsing, so it can be reported separately from either part. Only
+    /// the first call counts.
+    pub fn parsed(&mut self) {
+        if self.parsed.is_none() {
+            self.parsed = Some(Instant::now());
+            self.changed();
         }
-        self.part1 = Some(result)
     }
 
-    pub fn part2(&mut self, result: String) {
-        if self.part2.is_some() {
-            panic!()
+    pub fn part1(&mut self, answer: impl Into<Answer>) -> Result<(), OutputError> {
+        Self::answer(1, &mut self.part1, &mut self.part1_at, answer.into())?;
+        self.changed();
+        Ok(())
+    }
+
+    pub fn part2(&mut self, answer: impl Into<Answer>) -> Result<(), OutputError> {
+        Self::answer(2, &mut self.part2, &mut self.part2_at, answer.into())?;
+        self.changed();
+        Ok(())
+    }
+
+    fn answer(
+        part: usize,
+        slot: &mut Option<Answer>,
+        at: &mut Option<Instant>,
+        answer: Answer,
+    ) -> Result<(), OutputError> {
+        if let Some(first) = slot {
+            return Err(OutputError::AlreadyAnswered {
+                part,
+                first: first.clone(),
+                second: answer,
+            });
         }
-        self.part2 = Some(result)
+        *slot = Some(answer);
+        *at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Attaches a named value that isn't an answer but is worth showing alongside it, such as an
+    /// intermediate result or a rendering of the final state.
+    pub fn diagnostic(&mut self, name: &str, value: impl fmt::Display) {
+        self.diagnostics.push((name.to_owned(), value.to_string()));
+        self.changed();
+    }
+
+    pub fn get_part1(&self) -> Option<&Answer> {
+        self.part1.as_ref()
+    }
+
+    pub fn get_part2(&self) -> Option<&Answer> {
+        self.part2.as_ref()
     }
 
-    pub fn get_part1(&self) -> &Option<String> {
-        &self.part1
+    pub fn diagnostics(&self) -> &[(String, String)] {
+        &self.diagnostics
     }
 
-    pub fn get_part2(&self) -> &Option<String> {
-        &self.part2
+    pub fn timings(&self) -> Timings {
+        let mut last = self.started;
+        let mut since_last = |mark: Option<Instant>| {
+            let mark = mark?;
+            let dur = mark.saturating_duration_since(last);
+            last = last.max(mark);
+            Some(dur)
+        };
+        Timings {
+            parse: since_last(self.parsed),
+            part1: since_last(self.part1_at),
+            part2: since_last(self.part2_at),
+        }
     }
 }
@@ -1,4 +1,4 @@
-use crate::day_output::DayOutput;
+use crate::day_output::{DayError, DayOutput};
 
 mod day01;
 mod day02;
@@ -26,7 +26,7 @@ mod day23;
 mod day24;
 mod day25;
 
-type DayFn = fn(&str, &mut DayOutput) -> ();
+pub type DayFn = fn(&str, &mut DayOutput) -> Result<(), DayError>;
 
 pub const DAYS: &[(&str, DayFn)] = &[
     ("day01", day01::main),
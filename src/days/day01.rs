@@ -1,18 +1,21 @@
 use std::{collections::HashMap, iter::zip};
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut lefts = Vec::<u64>::new();
     let mut rights = Vec::<u64>::new();
 
-    for line in input.lines() {
-        let mut tokens = line.split_ascii_whitespace();
-        lefts.push(tokens.next().unwrap().parse::<u64>().unwrap());
-        rights.push(tokens.next().unwrap().parse::<u64>().unwrap());
-        assert!(tokens.next().is_none());
+    for line in parse::lines(input) {
+        let mut tokens = line.text.split_ascii_whitespace();
+        lefts.push(line.next_parse::<u64>(&mut tokens, "a left location ID")?);
+        rights.push(line.next_parse::<u64>(&mut tokens, "a right location ID")?);
+        line.finish(&mut tokens)?;
     }
-    assert_eq!(lefts.len(), rights.len());
+    output.parsed();
 
     lefts.sort();
     rights.sort();
@@ -21,7 +24,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
         .map(|(l, r)| l.abs_diff(*r))
         .sum::<u64>() as i64;
 
-    output.part1(sum_diffs.to_string());
+    output.part1(sum_diffs)?;
 
     let mut right_counts = HashMap::<u64, u64>::new();
     for r in &rights {
@@ -36,5 +39,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
         })
         .sum::<u64>() as i64;
 
-    output.part2(sim_score.to_string());
+    output.part2(sim_score)?;
+    Ok(())
 }
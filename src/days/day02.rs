@@ -1,19 +1,26 @@
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 #[derive(Debug)]
 struct Report {
     levels: Vec<u64>,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut reports = Vec::<Report>::new();
-    for line in input.lines() {
+    for line in parse::lines(input) {
         let mut report = Report { levels: vec![] };
-        for token in line.split_ascii_whitespace() {
-            report.levels.push(token.parse::<u64>().unwrap());
+        for token in line.text.split_ascii_whitespace() {
+            report.levels.push(line.parse::<u64>(token, "a level")?);
+        }
+        if report.levels.is_empty() {
+            return Err(line.end_error("a level").into());
         }
         reports.push(report);
     }
+    output.parsed();
 
     fn is_safe(levels: &[u64], reversed: bool, skip_idx: Option<usize>) -> bool {
         fn is_safe3<'a>(mut levels: impl Iterator<Item = &'a u64>) -> bool {
@@ -64,7 +71,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
         })
         .count();
 
-    output.part1(safe_count.to_string());
+    output.part1(safe_count)?;
 
     let safe_dampened_count = reports
         .iter()
@@ -82,5 +89,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
         })
         .count();
 
-    output.part2(safe_dampened_count.to_string());
+    output.part2(safe_dampened_count)?;
+    Ok(())
 }
@@ -1,17 +1,29 @@
-use crate::day_output::DayOutput;
-use regex::{Captures, Regex};
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
+use regex::Regex;
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mul_re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
     let do_re = Regex::new(r"do\(\)").unwrap();
     let dont_re = Regex::new(r"don't\(\)").unwrap();
 
-    fn mul(c: &Captures) -> i64 {
-        let get = |i: usize| c.get(i).unwrap().as_str().parse::<i64>().unwrap();
-        get(1) * get(2)
+    // Each mul's product, keyed by where it is in the input.
+    let mut muls = Vec::<((usize, usize), i64)>::new();
+    for line in parse::lines(input) {
+        for c in mul_re.captures_iter(line.text) {
+            let whole = c.get(0).unwrap();
+            let get = |i: usize| line.parse::<i64>(c.get(i).unwrap().as_str(), "a 64-bit number");
+            let product = get(1)?
+                .checked_mul(get(2)?)
+                .ok_or_else(|| line.error_at(whole.as_str(), "a product that fits in 64 bits"))?;
+            muls.push(((line.number, whole.start()), product));
+        }
     }
-    let part1 = mul_re.captures_iter(input).map(|c| mul(&c)).sum::<i64>();
-    output.part1(part1.to_string());
+
+    let part1 = muls.iter().map(|(_, v)| v).sum::<i64>();
+    output.part1(part1)?;
 
     #[derive(Debug)]
     enum Command {
@@ -20,17 +32,18 @@ pub fn main(input: &str, output: &mut DayOutput) {
         Dont,
     }
 
-    let mut commands = Vec::<(usize, Command)>::new();
-
-    mul_re.captures_iter(input).for_each(|c| {
-        commands.push((c.get(0).unwrap().start(), Command::Add(mul(&c))));
-    });
-    do_re.find_iter(input).for_each(|m| {
-        commands.push((m.start(), Command::Do));
-    });
-    dont_re.find_iter(input).for_each(|m| {
-        commands.push((m.start(), Command::Dont));
-    });
+    let mut commands = muls
+        .into_iter()
+        .map(|(at, v)| (at, Command::Add(v)))
+        .collect::<Vec<_>>();
+    for line in parse::lines(input) {
+        do_re.find_iter(line.text).for_each(|m| {
+            commands.push(((line.number, m.start()), Command::Do));
+        });
+        dont_re.find_iter(line.text).for_each(|m| {
+            commands.push(((line.number, m.start()), Command::Dont));
+        });
+    }
 
     commands.sort_by_key(|c| c.0);
 
@@ -47,5 +60,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
             Command::Dont => do_add = false,
         }
     }
-    output.part2(part2.to_string());
+    output.part2(part2)?;
+    Ok(())
 }
@@ -1,6 +1,8 @@
 use crate::{
-    day_output::DayOutput,
+    day_output::{DayError, DayOutput},
     facing::{Facing, FACINGS},
+    map::Map,
+    parse,
 };
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -11,88 +13,55 @@ enum Letter {
     S,
 }
 
-struct Row {
-    letters: Vec<Letter>,
-}
-
-struct WordSearch {
-    rows: Vec<Row>,
-}
-
-impl WordSearch {
-    fn at(&self, (x, y): (isize, isize)) -> Option<Letter> {
-        let x: usize = x.try_into().ok()?;
-        let y: usize = y.try_into().ok()?;
-        let row = self.rows.get(y)?;
-        row.letters.get(x).copied()
-    }
-}
-
-pub fn main(input: &str, output: &mut DayOutput) {
-    let mut rows = Vec::<Row>::new();
-    for row in input.lines() {
-        let mut letters = Vec::<Letter>::new();
-        for c in row.chars() {
-            let l = match c {
-                'X' => Letter::X,
-                'M' => Letter::M,
-                'A' => Letter::A,
-                'S' => Letter::S,
-                _ => panic!(),
-            };
-            letters.push(l);
-        }
-        rows.push(Row { letters });
-    }
-    let ws = WordSearch { rows };
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    let mut lines = parse::lines(input);
+    let (ws, _) = Map::parse(&mut lines, &[], |c| match c {
+        'X' => Ok(Letter::X),
+        'M' => Ok(Letter::M),
+        'A' => Ok(Letter::A),
+        'S' => Ok(Letter::S),
+        _ => Err("one of X, M, A or S"),
+    })?;
+    lines.finish()?;
+    output.parsed();
 
     let mut part1 = 0i64;
-    for (y, row) in ws.rows.iter().enumerate() {
-        for (x, letter) in row.letters.iter().enumerate() {
-            if *letter != Letter::X {
-                continue;
-            }
-            for d in FACINGS.iter() {
-                let mut pos = (x as isize, y as isize);
-                let mut matched = true;
-                for expected in [Letter::M, Letter::A, Letter::S] {
-                    pos = Facing::go(pos, *d);
-                    if Some(expected) == ws.at(pos) {
-                        continue;
-                    }
-                    matched = false;
-                    break;
-                }
-                if matched {
-                    part1 += 1;
+    for (start, letter) in ws.iter() {
+        if *letter != Letter::X {
+            continue;
+        }
+        for d in FACINGS.iter() {
+            let mut pos = start;
+            let mut matched = true;
+            for expected in [Letter::M, Letter::A, Letter::S] {
+                pos += *d;
+                if Some(expected) == ws.at(pos) {
+                    continue;
                 }
+                matched = false;
+                break;
+            }
+            if matched {
+                part1 += 1;
             }
         }
     }
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let mut part2 = 0i64;
-    for (y, row) in ws.rows.iter().enumerate() {
-        for (x, letter) in row.letters.iter().enumerate() {
-            if *letter != Letter::A {
-                continue;
-            }
-            let pos = (x as isize, y as isize);
-            let nesw = [
-                ws.at(Facing::go(pos, Facing::NE)),
-                ws.at(Facing::go(pos, Facing::SW)),
-            ];
-            let nwse = [
-                ws.at(Facing::go(pos, Facing::NW)),
-                ws.at(Facing::go(pos, Facing::SE)),
-            ];
-            fn is_mas(letters: &[Option<Letter>; 2]) -> bool {
-                letters.contains(&Some(Letter::M)) && letters.contains(&Some(Letter::S))
-            }
-            if is_mas(&nesw) && is_mas(&nwse) {
-                part2 += 1;
-            }
+    for (pos, letter) in ws.iter() {
+        if *letter != Letter::A {
+            continue;
+        }
+        let nesw = [ws.at(pos + Facing::NE), ws.at(pos + Facing::SW)];
+        let nwse = [ws.at(pos + Facing::NW), ws.at(pos + Facing::SE)];
+        fn is_mas(letters: &[Option<Letter>; 2]) -> bool {
+            letters.contains(&Some(Letter::M)) && letters.contains(&Some(Letter::S))
+        }
+        if is_mas(&nesw) && is_mas(&nwse) {
+            part2 += 1;
         }
     }
-    output.part2(part2.to_string())
+    output.part2(part2)?;
+    Ok(())
 }
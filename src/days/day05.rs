@@ -3,7 +3,10 @@ use std::{
     collections::{HashMap, HashSet},
 };
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 #[derive(Debug)]
 struct Rule {
@@ -16,30 +19,33 @@ struct Update {
     pages: Vec<usize>,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut rules = Vec::<Rule>::new();
     let mut updates = Vec::<Update>::new();
 
     let mut is_rules = true;
-    for line in input.lines() {
+    for line in parse::lines(input) {
         if is_rules {
-            if line.is_empty() {
+            if line.text.is_empty() {
                 is_rules = false;
                 continue;
             }
-            let mut rule_it = line.split('|');
-            let first = rule_it.next().unwrap().parse::<usize>().unwrap();
-            let second = rule_it.next().unwrap().parse::<usize>().unwrap();
+            let mut rule_it = line.text.split('|');
+            let first = line.next_parse::<usize>(&mut rule_it, "a page number")?;
+            let second = line.next_parse::<usize>(&mut rule_it, "a page number after '|'")?;
+            line.finish(&mut rule_it)?;
             rules.push(Rule { first, second });
         } else {
             updates.push(Update {
                 pages: line
+                    .text
                     .split(',')
-                    .map(|s| s.parse::<usize>().unwrap())
-                    .collect(),
+                    .map(|s| line.parse::<usize>(s, "a page number"))
+                    .collect::<Result<_, _>>()?,
             });
         }
     }
+    output.parsed();
 
     let mut forward_rules = HashMap::<usize, HashSet<usize>>::new();
     for rule in rules {
@@ -69,7 +75,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
         .map(middle_page)
         .sum::<usize>() as i64;
 
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let part2 = updates
         .iter()
@@ -82,5 +88,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
         .map(|u| middle_page(&u))
         .sum::<usize>() as i64;
 
-    output.part2(part2.to_string());
+    output.part2(part2)?;
+    Ok(())
 }
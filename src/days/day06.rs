@@ -1,9 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::{
-    day_output::DayOutput,
+    day_output::{DayError, DayOutput},
     direction::Direction,
-    map::{Map, Row},
+    map::Map,
+    parse,
+    point::Point,
 };
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -12,40 +14,25 @@ enum Tile {
     Obstruction,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
-    let mut rows = Vec::<Row<Tile>>::new();
-    let mut start_pos: Option<(usize, usize)> = None;
-    for (y, line) in input.lines().enumerate() {
-        let mut tiles = Vec::<Tile>::new();
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '.' => tiles.push(Tile::Space),
-                '#' => tiles.push(Tile::Obstruction),
-                '^' => {
-                    tiles.push(Tile::Space);
-                    assert!(start_pos.is_none());
-                    start_pos = Some((x, y));
-                }
-                _ => panic!(),
-            }
-        }
-        rows.push(Row { tiles });
-    }
-
-    let map = Map { rows };
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    let mut lines = parse::lines(input);
+    let (map, markers) = Map::parse(&mut lines, &['^'], |c| match c {
+        '.' | '^' => Ok(Tile::Space),
+        '#' => Ok(Tile::Obstruction),
+        _ => Err("'.', '#' or '^'"),
+    })?;
+    lines.finish()?;
+    let start_pos = markers.one('^', "a guard '^' somewhere in the map")?;
+    output.parsed();
 
     let mut dir = Direction::Up;
-    let start_pos = match start_pos {
-        Some(pos) => (pos.0 as isize, pos.1 as isize),
-        None => panic!(),
-    };
     let mut pos = start_pos;
-    let mut path_history = Vec::<((isize, isize), Direction)>::new();
+    let mut path_history = Vec::<(Point, Direction)>::new();
 
     loop {
         path_history.push((pos, dir));
 
-        let next_pos = Direction::go(pos, dir);
+        let next_pos = pos + dir;
         match map.at(next_pos) {
             Some(tile) => match tile {
                 Tile::Space => {
@@ -60,7 +47,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
     }
 
     let unique_positions = path_history.iter().map(|pd| pd.0).collect::<HashSet<_>>();
-    output.part1(unique_positions.len().to_string());
+    output.part1(unique_positions.len())?;
 
     let posdir_index = path_history
         .iter()
@@ -69,9 +56,9 @@ pub fn main(input: &str, output: &mut DayOutput) {
         .collect::<HashMap<_, _>>();
 
     let mut loop_count = 0i64;
-    let mut considered_obst_pos = HashSet::<(isize, isize)>::new();
+    let mut considered_obst_pos = HashSet::<Point>::new();
 
-    let mut new_visited = HashSet::<((isize, isize), Direction)>::new();
+    let mut new_visited = HashSet::<(Point, Direction)>::new();
     for (idx, (orig_pos, orig_dir)) in path_history.iter().enumerate() {
         let (obst_pos, _next_dir) = match path_history.get(idx + 1) {
             Some(h) => h,
@@ -95,7 +82,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
         let mut pos = *orig_pos;
         let mut dir = *orig_dir;
         loop {
-            let next_pos = Direction::go(pos, dir);
+            let next_pos = pos + dir;
             let next_tile = if next_pos == *obst_pos {
                 Tile::Obstruction
             } else {
@@ -126,5 +113,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
         }
     }
 
-    output.part2(loop_count.to_string());
+    output.part2(loop_count)?;
+    Ok(())
 }
@@ -1,4 +1,7 @@
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 struct Equation {
     tv: usize,
@@ -11,17 +14,24 @@ enum Operator {
     Concat,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut equations = Vec::<Equation>::new();
-    for line in input.lines() {
-        let (tv, nums) = line.split_once(':').unwrap();
-        let parse = |v: &str| v.parse::<usize>().unwrap();
+    for line in parse::lines(input) {
+        let (tv, nums) = line.split_once(":", "a test value followed by ':'")?;
+        let nums = nums
+            .split_ascii_whitespace()
+            .map(|v| line.parse::<usize>(v, "an operand"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if nums.is_empty() {
+            return Err(line.end_error("an operand").into());
+        }
         equations.push(Equation {
-            tv: parse(tv),
-            nums: nums.split_ascii_whitespace().map(parse).collect(),
+            tv: line.parse::<usize>(tv, "a test value")?,
+            nums,
         });
     }
     let equations = equations;
+    output.parsed();
 
     fn equation_is_true<const N: usize>(eq: &Equation, operators: &[Operator; N]) -> bool {
         let tv = eq.tv;
@@ -74,7 +84,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
             },
         )
         .sum::<usize>();
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let part2 = equations
         .iter()
@@ -85,5 +95,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
             }
         })
         .sum::<usize>();
-    output.part2(part2.to_string());
+    output.part2(part2)?;
+    Ok(())
 }
@@ -1,8 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::{
-    day_output::DayOutput,
-    map::{Map, Row},
+    day_output::{DayError, DayOutput},
+    map::Map,
+    parse,
+    point::Point,
 };
 
 #[derive(Copy, Clone)]
@@ -11,50 +13,37 @@ enum Tile {
     Antenna(char),
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
-    let mut rows = Vec::<Row<Tile>>::new();
-    for line in input.lines() {
-        let mut tiles = Vec::<Tile>::new();
-        for c in line.chars() {
-            let tile = match c {
-                '.' => Tile::Nothing,
-                a => Tile::Antenna(a),
-            };
-            tiles.push(tile);
-        }
-        rows.push(Row { tiles });
-    }
-    let map = Map { rows };
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    let mut lines = parse::lines(input);
+    let (map, _) = Map::parse(&mut lines, &[], |c| match c {
+        '.' => Ok(Tile::Nothing),
+        a => Ok(Tile::Antenna(a)),
+    })?;
+    lines.finish()?;
+    output.parsed();
 
-    let mut char_to_antennas = HashMap::<char, Vec<(isize, isize)>>::new();
+    let mut char_to_antennas = HashMap::<char, Vec<Point>>::new();
 
-    for (y, row) in map.rows.iter().enumerate() {
-        for (x, tile) in row.tiles.iter().enumerate() {
-            match tile {
-                Tile::Nothing => (),
-                Tile::Antenna(c) => char_to_antennas
-                    .entry(*c)
-                    .or_default()
-                    .push((x as isize, y as isize)),
-            }
+    for (pos, tile) in map.iter() {
+        match tile {
+            Tile::Nothing => (),
+            Tile::Antenna(c) => char_to_antennas.entry(*c).or_default().push(pos),
         }
     }
 
-    let mut antinodes = HashSet::<(isize, isize)>::new();
-    let mut resonants = HashSet::<(isize, isize)>::new();
+    let mut antinodes = HashSet::<Point>::new();
+    let mut resonants = HashSet::<Point>::new();
 
     for (_c, antennas) in char_to_antennas.iter() {
         for (i, a) in antennas.iter().enumerate() {
             for b in antennas[i + 1..].iter() {
-                let (ax, ay) = *a;
-                let (bx, by) = *b;
-                let dx = ax - bx;
-                let dy = ay - by;
+                let (a, b) = (*a, *b);
+                let delta = a - b;
 
                 // Part 1 antinodes.
-                let n0 = (ax + dx, ay + dy);
-                let n1 = (bx - dx, by - dy);
-                let mut add_antinode = |n: (isize, isize)| -> bool {
+                let n0 = a + delta;
+                let n1 = b - delta;
+                let mut add_antinode = |n: Point| -> bool {
                     match map.at(n) {
                         Some(_) => {
                             antinodes.insert(n);
@@ -67,7 +56,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
                 add_antinode(n1);
 
                 // Part 2 resonants.
-                let mut add_resonant = |n: (isize, isize)| -> bool {
+                let mut add_resonant = |n: Point| -> bool {
                     match map.at(n) {
                         Some(_) => {
                             resonants.insert(n);
@@ -76,17 +65,18 @@ pub fn main(input: &str, output: &mut DayOutput) {
                         None => false,
                     }
                 };
-                let mut from_a = (ax, ay);
+                let mut from_a = a;
                 while add_resonant(from_a) {
-                    from_a = (from_a.0 + dx, from_a.1 + dy);
+                    from_a += delta;
                 }
-                let mut from_b = (bx, by);
+                let mut from_b = b;
                 while add_resonant(from_b) {
-                    from_b = (from_b.0 - dx, from_b.1 - dy);
+                    from_b -= delta;
                 }
             }
         }
     }
-    output.part1(antinodes.len().to_string());
-    output.part2(resonants.len().to_string());
+    output.part1(antinodes.len())?;
+    output.part2(resonants.len())?;
+    Ok(())
 }
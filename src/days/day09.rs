@@ -1,4 +1,7 @@
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 #[derive(Debug, Copy, Clone)]
 struct File {
@@ -53,28 +56,33 @@ fn calc_checksum(blockmap: &[Block]) -> usize {
     checksum
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut entries = Vec::<Entry>::new();
     let mut is_file = true;
     let mut file_idx = 0u32;
-    for char in input.chars() {
+    let mut lines = parse::lines(input);
+    let line = lines.next_or("a disk map")?;
+    lines.finish()?;
+    if line.text.is_empty() {
+        return Err(line.end_error("a disk map").into());
+    }
+    for (x, char) in line.text.chars().enumerate() {
+        let len = char
+            .to_digit(10)
+            .ok_or_else(|| line.char_error(x, char, "a digit"))?;
         match is_file {
             true => {
-                let entry = Entry::File(File {
-                    id: file_idx,
-                    len: char.to_digit(10).unwrap(),
-                });
+                let entry = Entry::File(File { id: file_idx, len });
                 file_idx += 1;
                 entries.push(entry);
             }
-            false => entries.push(Entry::Space(Space {
-                len: char.to_digit(10).unwrap(),
-            })),
+            false => entries.push(Entry::Space(Space { len })),
         };
 
         is_file = !is_file;
     }
     let entries = entries;
+    output.parsed();
 
     let mut part1_bm = entries_to_bm(&entries);
 
@@ -108,7 +116,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
 
     let part1 = calc_checksum(&part1_bm);
 
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let mut part2_ents = entries.clone();
     let mut i = part2_ents.len();
@@ -154,5 +162,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
     }
 
     let part2 = calc_checksum(&entries_to_bm(&part2_ents));
-    output.part2(part2.to_string());
+    output.part2(part2)?;
+    Ok(())
 }
@@ -1,13 +1,19 @@
 use std::collections::HashMap;
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut stones = Vec::<usize>::new();
-    for num in input.split_ascii_whitespace() {
-        stones.push(num.parse::<usize>().unwrap());
+    for line in parse::lines(input) {
+        for num in line.text.split_ascii_whitespace() {
+            stones.push(line.parse::<usize>(num, "a stone number")?);
+        }
     }
     let stones = stones;
+    output.parsed();
 
     let get_stone_counts_after_iters = |iters: usize| -> usize {
         let mut cur_stone_counts = HashMap::<usize, usize>::new();
@@ -52,6 +58,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
         cur_stone_counts.values().sum()
     };
 
-    output.part1(get_stone_counts_after_iters(25).to_string());
-    output.part2(get_stone_counts_after_iters(75).to_string());
+    output.part1(get_stone_counts_after_iters(25))?;
+    output.part2(get_stone_counts_after_iters(75))?;
+    Ok(())
 }
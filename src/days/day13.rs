@@ -1,6 +1,9 @@
-use regex::{Captures, Regex};
+use regex::Regex;
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse::{self, Lines, ParseError},
+};
 
 #[derive(Debug)]
 struct Machine {
@@ -9,33 +12,35 @@ struct Machine {
     p: (isize, isize),
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let a_re = Regex::new(r"Button A: X\+(\d+), Y\+(\d+)").unwrap();
     let b_re = Regex::new(r"Button B: X\+(\d+), Y\+(\d+)").unwrap();
     let p_re = Regex::new(r"Prize: X=(\d+), Y=(\d+)").unwrap();
 
+    let parse_xy = |lines: &mut Lines, re: &Regex, expected: &str| {
+        let line = lines.next_or(expected)?;
+        let c = re.captures(line.text).ok_or_else(|| line.error(expected))?;
+        let parse = |i: usize| line.parse::<isize>(c.get(i).unwrap().as_str(), "a number");
+        Ok::<_, ParseError>((parse(1)?, parse(2)?))
+    };
+
     let mut machines = Vec::<Machine>::new();
-    let mut line_iter = input.lines().peekable();
+    let mut line_iter = parse::lines(input);
     while line_iter.peek().is_some() {
-        let a = line_iter.next().unwrap();
-        let b = line_iter.next().unwrap();
-        let p = line_iter.next().unwrap();
-
-        let a = a_re.captures(a).unwrap();
-        let b = b_re.captures(b).unwrap();
-        let p = p_re.captures(p).unwrap();
+        let a = parse_xy(&mut line_iter, &a_re, "Button A: X+<n>, Y+<n>")?;
+        let b = parse_xy(&mut line_iter, &b_re, "Button B: X+<n>, Y+<n>")?;
+        let p = parse_xy(&mut line_iter, &p_re, "Prize: X=<n>, Y=<n>")?;
 
-        let parse = |c: &Captures, i: usize| c.get(i).unwrap().as_str().parse::<isize>().unwrap();
+        machines.push(Machine { a, b, p });
 
-        machines.push(Machine {
-            a: (parse(&a, 1), parse(&a, 2)),
-            b: (parse(&b, 1), parse(&b, 2)),
-            p: (parse(&p, 1), parse(&p, 2)),
-        });
-
-        line_iter.next();
+        if let Some(line) = line_iter.next() {
+            if !line.text.is_empty() {
+                return Err(line.error("a blank line between machines").into());
+            }
+        }
     }
     let machines = machines;
+    output.parsed();
     let machines2 = machines
         .iter()
         .map(|m| Machine {
@@ -85,8 +90,9 @@ pub fn main(input: &str, output: &mut DayOutput) {
     }
 
     let part1 = machines.iter().filter_map(calc_cost).sum::<isize>();
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let part2 = machines2.iter().filter_map(calc_cost).sum::<isize>();
-    output.part2(part2.to_string());
+    output.part2(part2)?;
+    Ok(())
 }
@@ -2,12 +2,17 @@ use std::collections::HashMap;
 
 use regex::Regex;
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    params::ParamError,
+    parse,
+    point::Point,
+};
 
 #[derive(Debug, Copy, Clone)]
 struct Robot {
-    p: (isize, isize),
-    v: (isize, isize),
+    p: Point,
+    v: Point,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -16,43 +21,45 @@ struct Quadrant {
     y: (isize, isize),
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
-    let mut iter_lines = input.lines();
-    let mut sz_ln = iter_lines.next().unwrap().split_whitespace();
-    let sz_x = sz_ln.next().unwrap().parse::<isize>().unwrap();
-    let sz_y = sz_ln.next().unwrap().parse::<isize>().unwrap();
-    assert!(sz_ln.next().is_none());
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    // The example uses a smaller grid than the real input.
+    let param_sz = |name: &str, default: isize| -> Result<isize, DayError> {
+        match output.param(name, default)? {
+            sz if sz > 0 => Ok(sz),
+            sz => Err(ParamError {
+                name: name.to_owned(),
+                value: sz.to_string(),
+                expected: "positive grid size",
+            }
+            .into()),
+        }
+    };
+    let sz_x = param_sz("width", 101)?;
+    let sz_y = param_sz("height", 103)?;
 
     let robot_re = Regex::new(r"p=(.+),(.+) v=(.+),(.+)").unwrap();
 
     let mut robots = Vec::<Robot>::new();
-    for line in iter_lines {
-        let c = robot_re.captures(line).unwrap();
-        let parse = |i: usize| c.get(i).unwrap().as_str().parse::<isize>().unwrap();
+    for line in parse::lines(input) {
+        let c = robot_re
+            .captures(line.text)
+            .ok_or_else(|| line.error("p=<x>,<y> v=<x>,<y>"))?;
+        let parse = |i: usize| line.parse::<isize>(c.get(i).unwrap().as_str(), "a number");
         robots.push(Robot {
-            p: (parse(1), parse(2)),
-            v: (parse(3), parse(4)),
+            p: Point::new(parse(1)?, parse(2)?),
+            v: Point::new(parse(3)?, parse(4)?),
         });
     }
 
-    let sz = (sz_x, sz_y);
+    let sz = Point::new(sz_x, sz_y);
     let robots = robots;
+    output.parsed();
 
     let mut p1_robots = robots.clone();
 
-    fn simulate(sz: (isize, isize), iters: usize, robots: &mut [Robot]) {
-        let szx = sz.0;
-        let szy = sz.1;
+    fn simulate(sz: Point, iters: usize, robots: &mut [Robot]) {
         for r in robots.iter_mut() {
-            let px = &mut r.p.0;
-            let py = &mut r.p.1;
-            let vx = r.v.0;
-            let vy = r.v.1;
-            *px += vx * iters as isize;
-            *py += vy * iters as isize;
-
-            *px = px.rem_euclid(szx);
-            *py = py.rem_euclid(szy);
+            r.p = (r.p + r.v * iters as isize).rem_euclid(sz);
         }
     }
 
@@ -83,22 +90,22 @@ pub fn main(input: &str, output: &mut DayOutput) {
     for q in quadrants.iter() {
         let mut r_count = 0usize;
         for r in p1_robots.iter() {
-            let px = r.p.0;
-            let py = r.p.1;
+            let Point { x: px, y: py } = r.p;
             if q.x.0 <= px && q.x.1 > px && q.y.0 <= py && q.y.1 > py {
                 r_count += 1;
             }
         }
         part1 *= r_count;
     }
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let mut p2_robots = robots.clone();
 
-    let mut locs = HashMap::<(isize, isize), usize>::new();
+    let mut locs = HashMap::<Point, usize>::new();
     let mut iter_count = 0;
     let iter_jump = 1usize;
     loop {
+        output.check_cancelled()?;
         locs.clear();
         let mut any_non_unique = false;
         for r in p2_robots.iter() {
@@ -109,20 +116,22 @@ pub fn main(input: &str, output: &mut DayOutput) {
             }
         }
         if !any_non_unique {
+            let mut frame = String::new();
             for y in 0..sz_y {
                 for x in 0..sz_x {
-                    match locs.get(&(x, y)) {
-                        Some(c) => print!("{c}"),
-                        None => print!("."),
+                    match locs.get(&Point::new(x, y)) {
+                        Some(c) => frame.push_str(&c.to_string()),
+                        None => frame.push('.'),
                     }
                 }
-                println!();
+                frame.push('\n');
             }
-            println!("{iter_count}");
+            output.diagnostic("frame", frame);
             break;
         }
         simulate(sz, iter_jump, &mut p2_robots);
         iter_count += iter_jump;
     }
-    output.part2(iter_count.to_string());
+    output.part2(iter_count)?;
+    Ok(())
 }
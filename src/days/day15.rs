@@ -1,7 +1,9 @@
 use crate::{
-    day_output::DayOutput,
+    day_output::{DayError, DayOutput},
     direction::Direction,
-    map::{Map, Row},
+    map::Map,
+    parse,
+    point::Point,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -19,68 +21,48 @@ enum Tile2 {
     Wall,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
-    let mut is_map = true;
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    let mut lines = parse::lines(input);
+    let (map, markers) = Map::parse(&mut lines, &['@'], |c| match c {
+        '#' => Ok(Tile::Wall),
+        '.' | '@' => Ok(Tile::Empty),
+        'O' => Ok(Tile::Box),
+        _ => Err("one of '#', '.', 'O' or '@'"),
+    })?;
+    let start = markers.one('@', "a robot '@' in the warehouse map")?;
+
+    // Part 2's warehouse is twice as wide, with each box taking up two tiles.
+    let map2 = Map::from_fn(map.width() * 2, map.height(), |p| {
+        let left = p.x % 2 == 0;
+        match map.at(Point::new(p.x / 2, p.y)).unwrap() {
+            Tile::Empty => Tile2::Empty,
+            Tile::Box if left => Tile2::BoxL,
+            Tile::Box => Tile2::BoxR,
+            Tile::Wall => Tile2::Wall,
+        }
+    });
 
-    let mut rows = Vec::<Row<Tile>>::new();
-    let mut rows2 = Vec::<Row<Tile2>>::new();
     let mut commands = Vec::<Direction>::new();
-    let mut start: Option<(usize, usize)> = None;
-    for (y, line) in input.lines().enumerate() {
-        if is_map {
-            if line.is_empty() {
-                is_map = false;
-                continue;
-            }
-            let mut tiles = Vec::<Tile>::new();
-            let mut tiles2 = Vec::<Tile2>::new();
-            for (x, c) in line.chars().enumerate() {
-                let tile = match c {
-                    '#' => Tile::Wall,
-                    '.' => Tile::Empty,
-                    'O' => Tile::Box,
-                    '@' => {
-                        assert!(start.is_none());
-                        start = Some((x, y));
-                        Tile::Empty
-                    }
-                    _ => panic!(),
-                };
-                tiles.push(tile);
-                let tile2s = match tile {
-                    Tile::Empty => [Tile2::Empty, Tile2::Empty],
-                    Tile::Box => [Tile2::BoxL, Tile2::BoxR],
-                    Tile::Wall => [Tile2::Wall, Tile2::Wall],
-                };
-                tiles2.extend(tile2s);
-            }
-            rows.push(Row { tiles });
-            rows2.push(Row { tiles: tiles2 });
-        } else {
-            for c in line.chars() {
-                commands.push(match c {
-                    '^' => Direction::Up,
-                    '>' => Direction::Right,
-                    'v' => Direction::Down,
-                    '<' => Direction::Left,
-                    _ => panic!(),
-                });
-            }
+    for line in lines {
+        for (x, c) in line.text.chars().enumerate() {
+            commands.push(match c {
+                '^' => Direction::Up,
+                '>' => Direction::Right,
+                'v' => Direction::Down,
+                '<' => Direction::Left,
+                _ => return Err(line.char_error(x, c, "one of '^', '>', 'v' or '<'").into()),
+            });
         }
     }
-
-    let map = Map { rows };
-    let map2 = Map { rows: rows2 };
-
-    let start = start.unwrap();
+    output.parsed();
 
     // Part 1
     {
         let mut map = map.clone();
-        let mut pos = (start.0 as isize, start.1 as isize);
+        let mut pos = start;
 
         for c in commands.iter() {
-            let npos = Direction::go(pos, *c);
+            let npos = pos + *c;
             let can_move: bool;
             let mut cur_tile_pos = npos;
             loop {
@@ -95,7 +77,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
                         break;
                     }
                 }
-                cur_tile_pos = Direction::go(cur_tile_pos, *c);
+                cur_tile_pos += *c;
             }
             if can_move {
                 pos = npos;
@@ -108,32 +90,28 @@ pub fn main(input: &str, output: &mut DayOutput) {
         }
 
         let mut part1 = 0usize;
-        for (y, row) in map.rows.iter().enumerate() {
-            for (x, tile) in row.tiles.iter().enumerate() {
-                if *tile == Tile::Box {
-                    part1 += 100 * y + x;
-                }
+        for (p, tile) in map.iter() {
+            if *tile == Tile::Box {
+                part1 += 100 * p.y as usize + p.x as usize;
             }
         }
-        output.part1(part1.to_string());
+        output.part1(part1)?;
     }
 
     #[allow(dead_code)]
-    fn print_map2(map: &Map<Tile2>, pos: (isize, isize)) {
-        for (y, row) in map.rows.iter().enumerate() {
-            for (x, tile) in row.tiles.iter().enumerate() {
-                let mut c = match tile {
-                    Tile2::Empty => '.',
-                    Tile2::BoxL => '[',
-                    Tile2::BoxR => ']',
-                    Tile2::Wall => '#',
-                };
-                if (x as isize, y as isize) == pos {
-                    c = '@';
-                }
-                print!("{c}");
+    fn print_map2(map: &Map<Tile2>, pos: Point) {
+        for (p, tile) in map.iter() {
+            let c = match tile {
+                _ if p == pos => '@',
+                Tile2::Empty => '.',
+                Tile2::BoxL => '[',
+                Tile2::BoxR => ']',
+                Tile2::Wall => '#',
+            };
+            print!("{c}");
+            if p.x as usize == map.width() - 1 {
+                println!();
             }
-            println!();
         }
         println!();
     }
@@ -141,11 +119,11 @@ pub fn main(input: &str, output: &mut DayOutput) {
     // Part 2
     {
         let mut map = map2.clone();
-        let mut pos = (start.0 as isize * 2, start.1 as isize);
+        let mut pos = Point::new(start.x * 2, start.y);
 
-        let mut wavefront = Vec::<(isize, isize)>::new();
-        let mut next_wavefront = Vec::<(isize, isize)>::new();
-        let mut moves_to_apply = Vec::<((isize, isize), Tile2)>::new();
+        let mut wavefront = Vec::<Point>::new();
+        let mut next_wavefront = Vec::<Point>::new();
+        let mut moves_to_apply = Vec::<(Point, Tile2)>::new();
         for c in commands.iter() {
             wavefront.clear();
             next_wavefront.clear();
@@ -156,19 +134,19 @@ pub fn main(input: &str, output: &mut DayOutput) {
             let up_or_down = [Direction::Up, Direction::Down].contains(c);
             loop {
                 for wf in wavefront.drain(..) {
-                    let cur_tile_pos = Direction::go(wf, *c);
+                    let cur_tile_pos = wf + *c;
                     match map.at(cur_tile_pos).unwrap() {
                         Tile2::Empty => {}
                         Tile2::BoxL => {
                             next_wavefront.push(cur_tile_pos);
                             if up_or_down {
-                                next_wavefront.push((cur_tile_pos.0 + 1, cur_tile_pos.1));
+                                next_wavefront.push(cur_tile_pos + Direction::Right);
                             }
                         }
                         Tile2::BoxR => {
                             next_wavefront.push(cur_tile_pos);
                             if up_or_down {
-                                next_wavefront.push((cur_tile_pos.0 - 1, cur_tile_pos.1));
+                                next_wavefront.push(cur_tile_pos + Direction::Left);
                             }
                         }
                         Tile2::Wall => {
@@ -194,21 +172,20 @@ pub fn main(input: &str, output: &mut DayOutput) {
 
             // Now we've confirmed we can move, apply the move.
             for (cur_tile_pos, tile) in moves_to_apply.iter().rev() {
-                let npos = Direction::go(*cur_tile_pos, *c);
+                let npos = *cur_tile_pos + *c;
                 *map.at_mut(npos).unwrap() = *tile;
                 *map.at_mut(*cur_tile_pos).unwrap() = Tile2::Empty;
             }
-            pos = Direction::go(pos, *c);
+            pos += *c;
         }
 
         let mut part2 = 0usize;
-        for (y, row) in map.rows.iter().enumerate() {
-            for (x, tile) in row.tiles.iter().enumerate() {
-                if *tile == Tile2::BoxL {
-                    part2 += 100 * y + x;
-                }
+        for (p, tile) in map.iter() {
+            if *tile == Tile2::BoxL {
+                part2 += 100 * p.y as usize + p.x as usize;
             }
         }
-        output.part2(part2.to_string());
+        output.part2(part2)?;
     }
+    Ok(())
 }
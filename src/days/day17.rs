@@ -1,4 +1,7 @@
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 #[derive(Debug, Clone, Copy)]
 enum Instruction {
@@ -93,24 +96,34 @@ fn exec(instr: u8, op: u8, regs: &mut Registers, pc: &mut usize, mut out: impl F
     }
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
-    let mut lines_iter = input.lines();
-    let reg_a = lines_iter.next().unwrap();
-    let reg_b = lines_iter.next().unwrap();
-    let reg_c = lines_iter.next().unwrap();
-    lines_iter.next();
-    let program = lines_iter.next().unwrap();
-    assert!(lines_iter.next().is_none());
-
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    let mut lines_iter = parse::lines(input);
+    let mut register = |name: &str| -> Result<i64, DayError> {
+        let expected = format!("Register {name}: <n>");
+        let line = lines_iter.next_or(&expected)?;
+        let value = line.strip_prefix(&format!("Register {name}: "), &expected)?;
+        Ok(line.parse(value, "a number")?)
+    };
     let initial_regs = Registers {
-        a: reg_a[reg_a.find(':').unwrap() + 2..].parse().unwrap(),
-        b: reg_b[reg_b.find(':').unwrap() + 2..].parse().unwrap(),
-        c: reg_c[reg_c.find(':').unwrap() + 2..].parse().unwrap(),
+        a: register("A")?,
+        b: register("B")?,
+        c: register("C")?,
     };
-    let program = program[program.find(':').unwrap() + 2..]
+    let blank = lines_iter.next_or("a blank line")?;
+    if !blank.text.is_empty() {
+        return Err(blank.error("a blank line").into());
+    }
+    let program_line = lines_iter.next_or("Program: <n>,<n>,...")?;
+    let program = program_line
+        .strip_prefix("Program: ", "Program: <n>,<n>,...")?
         .split(',')
-        .map(|s| s.parse::<u8>().unwrap())
-        .collect::<Vec<_>>();
+        .map(|s| match program_line.parse::<u8>(s, "a 3-bit number")? {
+            v @ 0..=7 => Ok(v),
+            _ => Err(program_line.error_at(s, "a 3-bit number")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    lines_iter.finish()?;
+    output.parsed();
 
     // Part 1
     {
@@ -129,13 +142,9 @@ pub fn main(input: &str, output: &mut DayOutput) {
             }
         }
 
-        let part1 = outs
-            .iter()
-            .map(|v| v.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
+        let part1 = outs.iter().map(|v| v.to_string()).collect::<Vec<_>>();
 
-        output.part1(part1);
+        output.part1(part1)?;
     }
 
     // Part 2.
@@ -144,6 +153,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
     {
         let mut outs = Vec::<u8>::new();
         for reg_a in 0.. {
+            output.check_cancelled()?;
             let mut registers = Registers {
                 a: reg_a,
                 b: 0,
@@ -171,9 +181,10 @@ pub fn main(input: &str, output: &mut DayOutput) {
                 }
             }
             if success {
-                output.part2(reg_a.to_string());
+                output.part2(reg_a)?;
                 break;
             }
         }
     }
+    Ok(())
 }
@@ -1,13 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use crate::{
-    day_output::DayOutput,
-    direction::{Direction, DIRECTIONS},
-    map::{Map, Row},
+    day_output::{DayError, DayOutput},
+    map::Map,
+    params::ParamError,
+    parse,
+    point::Point,
+    search,
 };
 
 struct Byte {
-    pos: (isize, isize),
+    pos: Point,
     idx: usize,
 }
 
@@ -17,57 +20,48 @@ enum Tile {
     Byte(usize),
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut bytes = Vec::<Byte>::new();
-    let mut is_bytes = true;
-    let mut meta: Option<(isize, isize, usize)> = None;
-    for line in input.lines() {
-        if is_bytes {
-            if line.is_empty() {
-                is_bytes = false;
-            } else {
-                let mut elems = line.split(',');
-                let x = elems.next().unwrap().parse::<isize>().unwrap();
-                let y = elems.next().unwrap().parse::<isize>().unwrap();
-                assert!(elems.next().is_none());
-                bytes.push(Byte {
-                    pos: (x, y),
-                    idx: bytes.len(),
-                });
-            }
-        } else {
-            let mut elems = line.split_ascii_whitespace();
-            let x = elems.next().unwrap().parse::<isize>().unwrap();
-            let y = elems.next().unwrap().parse::<isize>().unwrap();
-            let num_falling = elems.next().unwrap().parse::<usize>().unwrap();
-            assert!(elems.next().is_none());
-            meta = Some((x, y, num_falling));
-        }
+    for line in parse::lines(input) {
+        let mut elems = line.text.split(',');
+        let x = line.next_parse::<isize>(&mut elems, "an x coordinate")?;
+        let y = line.next_parse::<isize>(&mut elems, "a y coordinate")?;
+        line.finish(&mut elems)?;
+        bytes.push(Byte {
+            pos: Point::new(x, y),
+            idx: bytes.len(),
+        });
     }
 
-    let (sx, sy, part1_falling) = meta.unwrap();
-    let sp = (0isize, 0isize);
-    let ep = (sx, sy);
+    // The example uses a smaller grid and fewer fallen bytes than the real input.
+    let sx = output.param("max_x", 70usize)? as isize;
+    let sy = output.param("max_y", 70usize)? as isize;
+    let part1_falling = output.param("part1_bytes", 1024usize)?;
+    if part1_falling == 0 {
+        return Err(ParamError {
+            name: "part1_bytes".to_owned(),
+            value: part1_falling.to_string(),
+            expected: "positive byte count",
+        }
+        .into());
+    }
+    let sp = Point::ZERO;
+    let ep = Point::new(sx, sy);
 
-    let bps = HashMap::<(isize, isize), usize>::from_iter(
-        bytes.iter().enumerate().map(|(i, b)| (b.pos, i)),
-    );
+    let bps = HashMap::<Point, usize>::from_iter(bytes.iter().enumerate().map(|(i, b)| (b.pos, i)));
 
-    let mut rows = Vec::<Row<Tile>>::new();
-    for y in 0..=sy {
-        rows.push(Row {
-            tiles: Vec::from_iter((0..=sx).map(|x| match bps.get(&(x, y)) {
-                Some(i) => Tile::Byte(*i),
-                None => Tile::Empty,
-            })),
-        });
-    }
-    let map = Map { rows };
+    let map = Map::from_fn(sx as usize + 1, sy as usize + 1, |pos| {
+        match bps.get(&pos) {
+            Some(i) => Tile::Byte(*i),
+            None => Tile::Empty,
+        }
+    });
+    output.parsed();
 
     #[allow(dead_code)]
     fn print_map(map: &Map<Tile>, max_fallen_idx: usize) {
-        for row in map.rows.iter() {
-            for t in row.tiles.iter() {
+        for row in map.rows() {
+            for t in row.iter() {
                 let c = match t {
                     Tile::Empty => '.',
                     Tile::Byte(i) => {
@@ -88,43 +82,20 @@ pub fn main(input: &str, output: &mut DayOutput) {
         assert_eq!(map.at(sp).unwrap(), Tile::Empty);
         assert_eq!(map.at(ep).unwrap(), Tile::Empty);
 
-        let mut visited = HashSet::<(isize, isize)>::new();
-        let mut heads = HashSet::<(isize, isize)>::new();
-        visited.insert(sp);
-        heads.insert(sp);
-        let mut next_heads = HashSet::<(isize, isize)>::new();
-        let mut steps = 0usize;
-        while !heads.is_empty() {
-            steps += 1;
-            for pos in heads.drain() {
-                for d in DIRECTIONS {
-                    let np = Direction::go(pos, d);
-                    if np == ep {
-                        return Some(steps);
-                    }
-                    let is_empty = match map.at(np) {
-                        Some(t) => match t {
-                            Tile::Empty => true,
-                            Tile::Byte(i) => i > max_fallen_idx,
-                        },
-                        None => false,
-                    };
-
-                    if is_empty && !visited.contains(&np) {
-                        visited.insert(np);
-                        next_heads.insert(np);
-                    }
-                }
-            }
-            heads.extend(next_heads.drain());
-        }
-        None
+        let is_empty = |p: Point| match map.at(p) {
+            Some(Tile::Empty) => true,
+            Some(Tile::Byte(i)) => i > max_fallen_idx,
+            None => false,
+        };
+        let successors = |p: &Point| p.neighbours4().filter(|n| is_empty(*n)).map(|n| (n, 1));
+        search::astar(sp, successors, |p| p.manhattan(ep), |p| *p == ep).map(|(steps, _)| steps)
     };
 
     let part1 = steps_to_exit(part1_falling - 1).unwrap();
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let first_failing_idx = bytes.partition_point(|b| steps_to_exit(b.idx).is_some());
     let first_byte = &bytes[first_failing_idx];
-    output.part2(format!("{},{}", first_byte.pos.0, first_byte.pos.1));
+    output.part2(first_byte.pos)?;
+    Ok(())
 }
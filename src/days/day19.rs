@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse::{self, Line, ParseError},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Stripe {
@@ -12,16 +15,30 @@ enum Stripe {
 }
 
 impl Stripe {
-    fn parse(c: char) -> Stripe {
+    fn parse(c: char) -> Option<Stripe> {
         match c {
-            'w' => Stripe::White,
-            'u' => Stripe::Blue,
-            'b' => Stripe::Black,
-            'r' => Stripe::Red,
-            'g' => Stripe::Green,
-            _ => panic!(),
+            'w' => Some(Stripe::White),
+            'u' => Some(Stripe::Blue),
+            'b' => Some(Stripe::Black),
+            'r' => Some(Stripe::Red),
+            'g' => Some(Stripe::Green),
+            _ => None,
         }
     }
+
+    /// Parses `part`, a slice of `line`'s text, as a sequence of stripes.
+    fn parse_all<'a>(line: &Line<'a>, part: &'a str) -> Result<Vec<Stripe>, ParseError> {
+        part.char_indices()
+            .map(|(i, c)| {
+                Stripe::parse(c).ok_or_else(|| {
+                    line.error_at(
+                        &part[i..i + c.len_utf8()],
+                        "one of 'w', 'u', 'b', 'r' or 'g'",
+                    )
+                })
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,25 +51,30 @@ struct Design {
     stripes: Vec<Stripe>,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
-    let mut iter_lines = input.lines();
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    let mut iter_lines = parse::lines(input);
     let mut towels = Vec::<Towel>::new();
-    for towel in iter_lines.next().unwrap().split(", ") {
+    let towels_line = iter_lines.next_or("a list of towels")?;
+    for towel in towels_line.text.split(", ") {
         towels.push(Towel {
-            stripes: towel.chars().map(Stripe::parse).collect(),
+            stripes: Stripe::parse_all(&towels_line, towel)?,
         });
     }
 
-    iter_lines.next();
+    let blank = iter_lines.next_or("a blank line")?;
+    if !blank.text.is_empty() {
+        return Err(blank.error("a blank line").into());
+    }
 
     let mut designs = Vec::<Design>::new();
     for design in iter_lines {
         designs.push(Design {
-            stripes: design.chars().map(Stripe::parse).collect(),
+            stripes: Stripe::parse_all(&design, design.text)?,
         });
     }
     let towels = towels;
     let designs = designs;
+    output.parsed();
 
     fn recurse<'a>(
         memo: &mut HashMap<&'a [Stripe], usize>,
@@ -86,6 +108,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
         }
         part2 += num_ways;
     }
-    output.part1(part1.to_string());
-    output.part2(part2.to_string());
+    output.part1(part1)?;
+    output.part2(part2)?;
+    Ok(())
 }
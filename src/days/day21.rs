@@ -1,9 +1,12 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::HashMap;
 
 use crate::{
-    day_output::DayOutput,
+    day_output::{DayError, DayOutput},
     direction::{Direction, DIRECTIONS},
-    map::{Map, Row},
+    map::Map,
+    parse,
+    point::Point,
+    search,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -37,18 +40,19 @@ enum DirPad {
 }
 
 impl DirPad {
-    fn pos(self) -> (isize, isize) {
-        match self {
+    fn pos(self) -> Point {
+        let (x, y) = match self {
             DirPad::Up => (1, 0),
             DirPad::Right => (2, 1),
             DirPad::Down => (1, 1),
             DirPad::Left => (0, 1),
             DirPad::A => (2, 0),
-        }
+        };
+        Point::new(x, y)
     }
 
-    fn at((x, y): (isize, isize)) -> Option<DirPad> {
-        match (x, y) {
+    fn at(p: Point) -> Option<DirPad> {
+        match (p.x, p.y) {
             (1, 0) => Some(DirPad::Up),
             (2, 1) => Some(DirPad::Right),
             (1, 1) => Some(DirPad::Down),
@@ -86,11 +90,11 @@ fn print_dirs(dirs: &[DirPad]) {
     println!();
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut inputs = Vec::<Input>::new();
-    for line in input.lines() {
+    for line in parse::lines(input) {
         let mut nums = Vec::<NumPad>::new();
-        for char in line.chars() {
+        for (x, char) in line.text.chars().enumerate() {
             let num = match char {
                 'A' => NumPad::_A,
                 '0' => NumPad::_0,
@@ -103,30 +107,27 @@ pub fn main(input: &str, output: &mut DayOutput) {
                 '7' => NumPad::_7,
                 '8' => NumPad::_8,
                 '9' => NumPad::_9,
-                _ => panic!(),
+                _ => return Err(line.char_error(x, char, "a digit or 'A'").into()),
             };
             nums.push(num);
         }
-        let cost = line.strip_suffix('A').unwrap().parse::<usize>().unwrap();
+        let code = line
+            .text
+            .strip_suffix('A')
+            .ok_or_else(|| line.end_error("a code ending in 'A'"))?;
+        let cost = line.parse::<usize>(code, "a numeric code")?;
         inputs.push(Input { cost, nums });
     }
     let inputs = inputs;
+    output.parsed();
 
-    let rows = vec![
-        Row {
-            tiles: vec![Some(NumPad::_7), Some(NumPad::_8), Some(NumPad::_9)],
-        },
-        Row {
-            tiles: vec![Some(NumPad::_4), Some(NumPad::_5), Some(NumPad::_6)],
-        },
-        Row {
-            tiles: vec![Some(NumPad::_1), Some(NumPad::_2), Some(NumPad::_3)],
-        },
-        Row {
-            tiles: vec![None, Some(NumPad::_0), Some(NumPad::_A)],
-        },
+    let rows = [
+        [Some(NumPad::_7), Some(NumPad::_8), Some(NumPad::_9)],
+        [Some(NumPad::_4), Some(NumPad::_5), Some(NumPad::_6)],
+        [Some(NumPad::_1), Some(NumPad::_2), Some(NumPad::_3)],
+        [None, Some(NumPad::_0), Some(NumPad::_A)],
     ];
-    let numpad = Map { rows };
+    let numpad = Map::from_fn(3, 4, |p| rows[p.y as usize][p.x as usize]);
 
     fn dirs_to_pad_inputs(dirs: &[Direction]) -> Vec<DirPad> {
         let mut dirpath = dirs.iter().map(|d| DirPad::convert(*d)).collect::<Vec<_>>();
@@ -134,6 +135,15 @@ pub fn main(input: &str, output: &mut DayOutput) {
         dirpath
     }
 
+    /// The directions stepped along a path of neighbouring points.
+    fn path_dirs(path: &[Point]) -> Vec<Direction> {
+        path.windows(2)
+            .map(|w| *DIRECTIONS.iter().find(|d| w[0] + **d == w[1]).unwrap())
+            .collect()
+    }
+
+    // Detours never make for fewer presses, so the searches below only compare shortest paths.
+
     fn calc_best_dir_input_path(
         memo: &mut HashMap<(DirPad, DirPad, usize), usize>,
         src: DirPad,
@@ -144,39 +154,15 @@ pub fn main(input: &str, output: &mut DayOutput) {
             return vec![];
         }
 
-        let mut heads = Vec::<((isize, isize), Vec<Direction>)>::new();
-        let mut visited = HashMap::<(isize, isize), Vec<Direction>>::new();
-        heads.push((src.pos(), vec![]));
-        visited.insert(heads[0].0, heads[0].1.clone());
-        let mut next_heads = Vec::<((isize, isize), Vec<Direction>)>::new();
-        while !heads.is_empty() {
-            for (pos, path) in heads.drain(..) {
-                for d in DIRECTIONS {
-                    let npos = Direction::go(pos, d);
-                    if DirPad::at(npos).is_some() {
-                        let entry = visited.entry(npos);
-                        let mut npath = path.clone();
-                        npath.push(d);
-                        let should_add = match &entry {
-                            Entry::Occupied(e) => {
-                                let mut cost = |ds: &Vec<Direction>| -> usize {
-                                    let pad_inputs = dirs_to_pad_inputs(ds);
-                                    calc_dir_inputs_cost(memo, &pad_inputs, iters)
-                                };
-                                cost(&npath) <= cost(e.get())
-                            }
-                            Entry::Vacant(_) => true,
-                        };
-                        if should_add {
-                            entry.insert_entry(npath.clone());
-                            next_heads.push((npos, npath));
-                        }
-                    }
-                }
-            }
-            heads.append(&mut next_heads);
-        }
-        visited.remove(&DirPad::pos(dst)).unwrap()
+        let paths = search::bfs([src.pos()], |p| {
+            p.neighbours4().filter(|n| DirPad::at(*n).is_some())
+        });
+        paths
+            .paths_to(&dst.pos())
+            .iter()
+            .map(|path| path_dirs(path))
+            .min_by_key(|ds| calc_dir_inputs_cost(memo, &dirs_to_pad_inputs(ds), iters))
+            .unwrap()
     }
 
     fn calc_dir_input_cost(
@@ -223,50 +209,26 @@ pub fn main(input: &str, output: &mut DayOutput) {
     ) -> HashMap<(NumPad, NumPad), Vec<Direction>> {
         let mut memo = HashMap::<(DirPad, DirPad, usize), usize>::new();
         let mut best_numpad_paths = HashMap::<(NumPad, NumPad), Vec<Direction>>::new();
-        for (y, row) in numpad.rows.iter().enumerate() {
-            for (x, num) in row.tiles.iter().enumerate() {
-                let num = match num {
-                    Some(num) => *num,
-                    None => continue,
-                };
-                let mut heads = Vec::<((isize, isize), Vec<Direction>)>::new();
-                let mut visited = HashMap::<(isize, isize), Vec<Direction>>::new();
-                heads.push(((x as isize, y as isize), vec![]));
-                visited.insert(heads[0].0, heads[0].1.clone());
-                let mut next_heads = Vec::<((isize, isize), Vec<Direction>)>::new();
-                while !heads.is_empty() {
-                    for (pos, path) in heads.drain(..) {
-                        for d in DIRECTIONS {
-                            let npos = Direction::go(pos, d);
-                            if numpad.at(npos).flatten().is_none() {
-                                continue;
-                            }
-                            let entry = visited.entry(npos);
-                            let mut npath = path.clone();
-                            npath.push(d);
-                            let should_add = match &entry {
-                                Entry::Occupied(e) => {
-                                    let mut cost = |ds: &Vec<Direction>| -> usize {
-                                        let pad_inputs = dirs_to_pad_inputs(ds);
-                                        calc_dir_inputs_cost(&mut memo, &pad_inputs, iters)
-                                    };
-                                    cost(&npath) <= cost(e.get())
-                                }
-                                Entry::Vacant(_) => true,
-                            };
-                            if should_add {
-                                entry.insert_entry(npath.clone());
-                                next_heads.push((npos, npath));
-                            }
-                        }
-                    }
-                    heads.append(&mut next_heads);
-                }
-                for (pos, path) in visited {
-                    let src = num;
-                    let dst = numpad.at(pos).unwrap().unwrap();
-                    best_numpad_paths.insert((src, dst), path);
-                }
+        for (start, num) in numpad.iter() {
+            let num = match num {
+                Some(num) => *num,
+                None => continue,
+            };
+            let paths = search::bfs([start], |p| {
+                p.neighbours4()
+                    .filter(|n| numpad.at(*n).flatten().is_some())
+            });
+            for (pos, _) in paths.distances() {
+                let best = paths
+                    .paths_to(pos)
+                    .iter()
+                    .map(|path| path_dirs(path))
+                    .min_by_key(|ds| {
+                        calc_dir_inputs_cost(&mut memo, &dirs_to_pad_inputs(ds), iters)
+                    })
+                    .unwrap();
+                let dst = numpad.at(*pos).unwrap().unwrap();
+                best_numpad_paths.insert((num, dst), best);
             }
         }
         assert_eq!(best_numpad_paths.len(), 11 * 11);
@@ -295,8 +257,9 @@ pub fn main(input: &str, output: &mut DayOutput) {
     };
 
     let part1 = calc_complexity(2);
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let part2 = calc_complexity(25);
-    output.part2(part2.to_string());
+    output.part2(part2)?;
+    Ok(())
 }
@@ -1,15 +1,19 @@
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 #[derive(Debug, Clone, Copy)]
 struct SecretNumber(usize);
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut secrets = Vec::<SecretNumber>::new();
-    for line in input.lines() {
-        let sn = line.parse::<usize>().unwrap();
+    for line in parse::lines(input) {
+        let sn = line.parse::<usize>(line.text, "a secret number")?;
         secrets.push(SecretNumber(sn));
     }
     let secrets = secrets;
+    output.parsed();
 
     let mut all_frequencies = vec![0; 2usize.pow(20)];
     let mut part1 = 0usize;
@@ -63,8 +67,9 @@ pub fn main(input: &str, output: &mut DayOutput) {
             }
         }
     }
-    output.part1(part1.to_string());
+    output.part1(part1)?;
 
     let part2 = all_frequencies.iter().max().unwrap();
-    output.part2(part2.to_string());
+    output.part2(*part2)?;
+    Ok(())
 }
@@ -3,7 +3,10 @@ use std::{
     rc::Rc,
 };
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct ComputerName(Rc<String>);
@@ -18,19 +21,20 @@ struct Computer {
     conns: HashSet<ComputerId>,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut links = Vec::<Link>::new();
-    for line in input.lines() {
-        let mut iter_computers = line.split("-");
-        let first = iter_computers.next().unwrap();
-        let second = iter_computers.next().unwrap();
-        assert!(iter_computers.next().is_none());
+    for line in parse::lines(input) {
+        let mut iter_computers = line.text.split("-");
+        let first = line.next(&mut iter_computers, "a computer name")?;
+        let second = line.next(&mut iter_computers, "'-' and a second computer name")?;
+        line.finish(&mut iter_computers)?;
         links.push(Link(
             ComputerName(Rc::new(first.to_owned())),
             ComputerName(Rc::new(second.to_owned())),
         ));
     }
     let links = links;
+    output.parsed();
 
     let mut direct_conns = HashMap::<ComputerName, HashSet<ComputerName>>::new();
     let mut add_direct_conn = |a: ComputerName, b: ComputerName| {
@@ -92,7 +96,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
                 part1 += 1;
             }
         }
-        output.part1(part1.to_string());
+        output.part1(part1)?;
     }
 
     // Part 2
@@ -134,6 +138,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
             .iter()
             .map(|s| computers[s.0 as usize].name.0.to_string())
             .collect::<Vec<_>>();
-        output.part2(last.join(","));
+        output.part2(last)?;
     }
+    Ok(())
 }
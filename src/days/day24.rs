@@ -3,7 +3,10 @@ use std::{
     rc::Rc,
 };
 
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
 
 #[derive(Debug, Clone)]
 struct Input {
@@ -36,43 +39,48 @@ struct Gate {
     out: Rc<String>,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut inputs = Vec::<Input>::new();
     let mut gates = Vec::<Gate>::new();
     let mut is_input = true;
-    for line in input.lines() {
+    for line in parse::lines(input) {
         if is_input {
-            if line.is_empty() {
+            if line.text.is_empty() {
                 is_input = false;
             } else {
-                let spl = line.split_once(": ").unwrap();
+                let spl = line.split_once(": ", "<wire>: <0 or 1>")?;
                 inputs.push(Input {
                     name: Rc::new(spl.0.to_owned()),
                     val: match spl.1 {
                         "0" => false,
                         "1" => true,
-                        _ => panic!(),
+                        _ => return Err(line.error_at(spl.1, "0 or 1").into()),
                     },
                 });
             }
         } else {
-            let mut iter_token = line.split_ascii_whitespace();
-            let in0 = Rc::new(iter_token.next().unwrap().to_owned());
-            let op = match iter_token.next().unwrap() {
+            let mut iter_token = line.text.split_ascii_whitespace();
+            let in0 = Rc::new(line.next(&mut iter_token, "an input wire")?.to_owned());
+            let op_token = line.next(&mut iter_token, "AND, XOR or OR")?;
+            let op = match op_token {
                 "AND" => Op::And,
                 "XOR" => Op::Xor,
                 "OR" => Op::Or,
-                _ => panic!(),
+                _ => return Err(line.error_at(op_token, "AND, XOR or OR").into()),
             };
-            let in1 = Rc::new(iter_token.next().unwrap().to_owned());
-            iter_token.next().unwrap();
-            let out = Rc::new(iter_token.next().unwrap().to_owned());
-            assert!(iter_token.next().is_none());
+            let in1 = Rc::new(line.next(&mut iter_token, "an input wire")?.to_owned());
+            let arrow = line.next(&mut iter_token, "->")?;
+            if arrow != "->" {
+                return Err(line.error_at(arrow, "->").into());
+            }
+            let out = Rc::new(line.next(&mut iter_token, "an output wire")?.to_owned());
+            line.finish(&mut iter_token)?;
             gates.push(Gate { in0, in1, op, out });
         }
     }
     let inputs = inputs;
     let gates = gates;
+    output.parsed();
 
     fn bool_to_bit(b: bool, bit: usize) -> usize {
         (match b {
@@ -155,7 +163,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
     // Part 1
     {
         let part1 = simulate(&gates, &[], &mut make_inputs_to_val(&inputs));
-        output.part1(part1.to_string());
+        output.part1(part1)?;
     }
 
     // Part 2
@@ -257,14 +265,19 @@ pub fn main(input: &str, output: &mut DayOutput) {
                     }
                 }
                 if possible_fixes.is_empty() {
-                    println!("No fix found from bit {i}");
+                    output.diagnostic("no_fix_from_bit", i);
                     break;
                 } else if possible_fixes.len() == 1 {
                     fixing_swaps.insert(possible_fixes.iter().next().unwrap().clone());
                 }
             }
         }
-        println!("Swaps: {fixing_swaps:?}");
+        let mut swaps = fixing_swaps
+            .iter()
+            .map(|(a, b)| format!("{a}<->{b}"))
+            .collect::<Vec<_>>();
+        swaps.sort();
+        output.diagnostic("swaps", swaps.join(" "));
 
         let mut part2 = Vec::<String>::new();
         for f in fixing_swaps.iter() {
@@ -272,6 +285,7 @@ pub fn main(input: &str, output: &mut DayOutput) {
             part2.push(f.1.to_string());
         }
         part2.sort();
-        output.part2(part2.join(","));
+        output.part2(part2)?;
     }
+    Ok(())
 }
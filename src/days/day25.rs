@@ -1,4 +1,8 @@
-use crate::day_output::DayOutput;
+use crate::{
+    day_output::{DayError, DayOutput},
+    map::Map,
+    parse,
+};
 
 #[derive(Debug, Clone)]
 struct Key {
@@ -16,57 +20,47 @@ enum Elem {
     Metal,
 }
 
-pub fn main(input: &str, output: &mut DayOutput) {
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
     let mut keys = Vec::<Key>::new();
     let mut locks = Vec::<Lock>::new();
 
-    let mut add = |buf: &[Vec<Elem>]| {
-        if buf.first().unwrap().iter().all(|e| *e == Elem::Empty) {
+    let mut lines = parse::lines(input);
+    while let Some(top) = lines.peek() {
+        let (schematic, _) = Map::parse(&mut lines, &[], |c| match c {
+            '.' => Ok(Elem::Empty),
+            '#' => Ok(Elem::Metal),
+            _ => Err("'.' or '#'"),
+        })?;
+        let rows = schematic.rows().collect::<Vec<_>>();
+        let first = rows[0];
+        if first.iter().all(|e| *e == Elem::Empty) {
             // Key
             let mut heights = Vec::<usize>::new();
-            for x in 0..buf.first().unwrap().len() {
-                let cur_height = buf
+            for x in 0..first.len() {
+                let cur_height = rows
                     .iter()
-                    .take(buf.len() - 1)
+                    .take(rows.len() - 1)
                     .filter(|r| r[x] == Elem::Metal)
                     .count();
                 heights.push(cur_height);
             }
             keys.push(Key { heights });
         } else {
-            assert!(buf.first().unwrap().iter().all(|e| *e == Elem::Metal));
+            if !first.iter().all(|e| *e == Elem::Metal) {
+                return Err(top
+                    .error("a schematic top row of all '.' or all '#'")
+                    .into());
+            }
             // Lock
             let mut heights = Vec::<usize>::new();
-            for x in 0..buf.last().unwrap().len() {
-                let cur_height = buf.iter().skip(1).filter(|r| r[x] == Elem::Metal).count();
+            for x in 0..first.len() {
+                let cur_height = rows.iter().skip(1).filter(|r| r[x] == Elem::Metal).count();
                 heights.push(cur_height);
             }
             locks.push(Lock { heights });
         }
-    };
-
-    let mut buf = Vec::<Vec<Elem>>::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            add(&buf);
-            buf.clear();
-        } else {
-            let mut rows = Vec::<Elem>::new();
-            for char in line.chars() {
-                let e = match char {
-                    '.' => Elem::Empty,
-                    '#' => Elem::Metal,
-                    _ => panic!(),
-                };
-                rows.push(e);
-            }
-            buf.push(rows);
-        }
-    }
-    if !buf.is_empty() {
-        add(&buf);
     }
+    output.parsed();
 
     let mut part1 = 0usize;
     for k in keys.iter() {
@@ -83,5 +77,6 @@ pub fn main(input: &str, output: &mut DayOutput) {
             }
         }
     }
-    output.part1(part1.to_string());
+    output.part1(part1)?;
+    Ok(())
 }
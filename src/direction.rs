@@ -1,3 +1,5 @@
+use crate::point::Point;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Direction {
     Up,
@@ -7,16 +9,13 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn go((x, y): (isize, isize), d: Direction) -> (isize, isize) {
-        Direction::go_n((x, y), d, 1)
-    }
-
-    pub fn go_n((x, y): (isize, isize), d: Direction, n: isize) -> (isize, isize) {
-        match d {
-            Direction::Up => (x, y - n),
-            Direction::Right => (x + n, y),
-            Direction::Down => (x, y + n),
-            Direction::Left => (x - n, y),
+    /// The step one tile in this direction, so `p + d` is the neighbouring point.
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
         }
     }
 
@@ -0,0 +1,130 @@
+use std::{fmt, process::ExitCode, time::Duration};
+
+/// A part whose answer didn't match its expected answer.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub day: String,
+    pub input: String,
+    pub part: usize,
+    pub expected: String,
+    /// `None` if the solver produced no answer for this part at all.
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Mismatch {
+            day,
+            input,
+            part,
+            expected,
+            actual,
+        } = self;
+        match actual {
+            Some(actual) => write!(
+                f,
+                "{day} input '{input}' part {part}: expected {expected} but got {actual}"
+            ),
+            None => write!(
+                f,
+                "{day} input '{input}' part {part}: expected {expected} but got no result"
+            ),
+        }
+    }
+}
+
+/// A solver run that panicked, or whose input or parameters couldn't even be read.
+#[derive(Debug, Clone)]
+pub struct Crash {
+    pub message: String,
+    /// Where the panic happened, as `file:line:column`.
+    pub location: Option<String>,
+}
+
+impl fmt::Display for Crash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "panicked at {location}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Failure {
+    Mismatch(Mismatch),
+    /// The solver stopped with an error of its own.
+    Solver {
+        day: String,
+        input: String,
+        message: String,
+    },
+    Crash {
+        day: String,
+        input: String,
+        crash: Crash,
+    },
+    Timeout {
+        day: String,
+        input: String,
+        limit: Duration,
+    },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Mismatch(m) => write!(f, "{m}"),
+            Failure::Solver {
+                day,
+                input,
+                message,
+            } => write!(f, "{day} input '{input}' {message}"),
+            Failure::Crash { day, input, crash } => {
+                write!(f, "{day} input '{input}' crashed: {crash}")
+            }
+            Failure::Timeout { day, input, limit } => {
+                write!(f, "{day} input '{input}' timed out after {limit:#?}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The command line named a day or input that doesn't exist.
+    Usage(String),
+    /// At least one part didn't produce its expected answer, or a solver failed, crashed or ran
+    /// out of time.
+    Failed(Vec<Failure>),
+    /// Reading or writing a harness file failed.
+    Io(String),
+}
+
+impl Error {
+    /// Exit codes are distinct so scripts can tell a regression from a bad invocation. A solver
+    /// panic is caught and counts as a failure; anything else that panics keeps Rust's default
+    /// of 101.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::Failed(_) => ExitCode::from(1),
+            Error::Usage(_) => ExitCode::from(2),
+            Error::Io(_) => ExitCode::from(3),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg) | Error::Io(msg) => write!(f, "{msg}"),
+            Error::Failed(failures) => {
+                write!(f, "{} failure(s):", failures.len())?;
+                for m in failures {
+                    write!(f, "\n    {m}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use crate::point::Point;
+
 #[derive(Copy, Clone, Debug)]
-pub(crate) enum Facing {
+pub enum Facing {
     N,
     NE,
     E,
@@ -11,21 +13,23 @@ pub(crate) enum Facing {
 }
 
 impl Facing {
-    pub(crate) fn go((x, y): (isize, isize), d: Facing) -> (isize, isize) {
-        match d {
-            Facing::N => (x, y - 1),
-            Facing::NE => (x + 1, y - 1),
-            Facing::E => (x + 1, y),
-            Facing::SE => (x + 1, y + 1),
-            Facing::S => (x, y + 1),
-            Facing::SW => (x - 1, y + 1),
-            Facing::W => (x - 1, y),
-            Facing::NW => (x - 1, y - 1),
-        }
+    /// The step one tile in this direction, so `p + f` is the neighbouring point.
+    pub fn offset(self) -> Point {
+        let (x, y) = match self {
+            Facing::N => (0, -1),
+            Facing::NE => (1, -1),
+            Facing::E => (1, 0),
+            Facing::SE => (1, 1),
+            Facing::S => (0, 1),
+            Facing::SW => (-1, 1),
+            Facing::W => (-1, 0),
+            Facing::NW => (-1, -1),
+        };
+        Point::new(x, y)
     }
 }
 
-pub(crate) const FACINGS: &[Facing] = &[
+pub const FACINGS: &[Facing] = &[
     Facing::N,
     Facing::NE,
     Facing::E,
@@ -0,0 +1,37 @@
+//! A fast non-cryptographic hasher for maps and sets keyed by small values.
+
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// The multiply-and-rotate hash from rustc, which is much faster than the default SipHash for
+/// small keys such as points, e.g. in `search`'s maps of reached states. It's not DoS resistant,
+/// which doesn't matter for puzzle input.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher(u64);
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+
+    fn write_isize(&mut self, n: isize) {
+        self.write_u64(n as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
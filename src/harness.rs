@@ -0,0 +1,665 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    day_output::{CancelToken, DayError, DayOutput, Progress},
+    days::{DayFn, DAYS},
+    error::{Crash, Error},
+    params::Params,
+    report::{PartReport, RunReport, Status},
+};
+
+/// A single input for a day, loaded along with any expected answers.
+pub struct Case {
+    pub day: &'static str,
+    pub f: DayFn,
+    pub name: String,
+    /// The input directory the case was loaded from, or `None` for an ad hoc input.
+    pub dir: Option<PathBuf>,
+    /// The puzzle input, or why it couldn't be read. A read failure is reported as a crash when
+    /// the case is run, so it doesn't stop other cases loading.
+    pub input: Result<String, String>,
+    /// Parameters from the input's `params.toml`, if it has one.
+    pub params: Params,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Case {
+    pub fn run(&self) -> RunReport {
+        let execution = execute(
+            self.f,
+            &self.input,
+            &self.params,
+            CancelToken::default(),
+            None,
+        );
+        self.report(execution, None)
+    }
+
+    /// Runs the solver on its own thread, giving up on it after `timeout`. The solver is asked to
+    /// stop through its `DayOutput`, and anything it answered before stopping is kept. A solver
+    /// that doesn't check for cancellation is left running in the background while the harness
+    /// moves on, reporting whatever it had answered by then.
+    pub fn run_with_timeout(&self, timeout: Duration) -> RunReport {
+        let cancel = CancelToken::default();
+        let (tx, rx) = mpsc::channel();
+        let f = self.f;
+        let input = self.input.clone();
+        let params = self.params.clone();
+        let token = cancel.clone();
+        let progress = Progress::default();
+        let shared = progress.clone();
+        let started = Instant::now();
+        thread::spawn(move || {
+            // The receiver is gone if the harness has already given up on this run.
+            let _ = tx.send(execute(f, &input, &params, token, Some(shared)));
+        });
+
+        match rx.recv_timeout(timeout) {
+            Ok(execution) => self.report(execution, None),
+            Err(_) => {
+                cancel.cancel();
+                let execution = rx.recv_timeout(CANCEL_GRACE).unwrap_or_else(|_| Execution {
+                    output: progress.latest().unwrap_or_default(),
+                    duration: started.elapsed(),
+                    error: None,
+                    crash: None,
+                });
+                self.report(execution, Some(timeout))
+            }
+        }
+    }
+
+    fn report(&self, execution: Execution, timeout: Option<Duration>) -> RunReport {
+        let Execution {
+            output,
+            duration,
+            error,
+            crash,
+        } = execution;
+
+        let mut parts = [
+            PartReport::new(output.get_part1(), &self.part1),
+            PartReport::new(output.get_part2(), &self.part2),
+        ];
+        let unfinished = match (&crash, timeout) {
+            (Some(_), _) => Some(Status::Crash),
+            (None, Some(_)) => Some(Status::Timeout),
+            (None, None) => None,
+        };
+        if let Some(status) = unfinished {
+            for p in parts.iter_mut().filter(|p| p.actual.is_none()) {
+                p.status = status;
+            }
+        }
+
+        RunReport {
+            day: self.day.to_owned(),
+            input: self.name.clone(),
+            duration,
+            timings: output.timings(),
+            parts,
+            diagnostics: output.diagnostics().to_vec(),
+            error: error
+                .filter(|e| !matches!(e, DayError::Cancelled))
+                .map(|e| e.to_string()),
+            crash,
+            timeout,
+        }
+    }
+}
+
+/// Runs `cases` on up to `jobs` threads, each optionally limited to `timeout`. Reports are handed
+/// to `finished` in the order of `cases` regardless of which finishes first, and `started` is
+/// called for each case just before that, once every earlier case has finished. The returned
+/// reports are in the same order.
+pub fn run_cases(
+    cases: &[Case],
+    jobs: usize,
+    timeout: Option<Duration>,
+    mut started: impl FnMut(&Case),
+    mut finished: impl FnMut(&RunReport),
+) -> Vec<RunReport> {
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel::<(usize, RunReport)>();
+
+    thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, cases.len().max(1)) {
+            let tx = tx.clone();
+            let next = &next;
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(case) = cases.get(i) else {
+                    break;
+                };
+                let report = match timeout {
+                    Some(timeout) => case.run_with_timeout(timeout),
+                    None => case.run(),
+                };
+                if tx.send((i, report)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = Vec::<Option<RunReport>>::new();
+        pending.resize_with(cases.len(), || None);
+        let mut reports = Vec::<RunReport>::with_capacity(cases.len());
+        if let Some(first) = cases.first() {
+            started(first);
+        }
+        for (i, report) in rx.iter() {
+            pending[i] = Some(report);
+            while let Some(report) = pending.get_mut(reports.len()).and_then(Option::take) {
+                finished(&report);
+                reports.push(report);
+                if let Some(case) = cases.get(reports.len()) {
+                    started(case);
+                }
+            }
+        }
+        reports
+    })
+}
+
+/// How long a cancelled solver gets to notice and hand back its partial output.
+const CANCEL_GRACE: Duration = Duration::from_secs(1);
+
+/// The directories that inputs are loaded from, each laid out as `<root>/<day>/<input>/`.
+///
+/// Roots are layered: a day's inputs are the union of its input directories across all roots,
+/// and where more than one root has an input directory of the same name, the earliest root wins
+/// and supplies all of that input's files. `--inputs-dir` roots come first, in the order given,
+/// then those in `AOC24_INPUTS` (a path list, like `PATH`). With neither, the root is `./inputs`.
+#[derive(Debug, Clone)]
+pub struct InputRoots(Vec<PathBuf>);
+
+impl InputRoots {
+    pub fn new(cli_roots: &[PathBuf]) -> InputRoots {
+        let mut roots = cli_roots.to_vec();
+        if let Some(env_roots) = std::env::var_os("AOC24_INPUTS") {
+            roots.extend(std::env::split_paths(&env_roots).filter(|p| !p.as_os_str().is_empty()));
+        }
+        if roots.is_empty() {
+            roots.push(PathBuf::from("./inputs"));
+        }
+        InputRoots(roots)
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.0
+    }
+
+    /// The directory that input `name` of `day` is loaded from, if any root has it.
+    pub fn input_dir(&self, day: &str, name: &str) -> Option<PathBuf> {
+        self.0
+            .iter()
+            .map(|root| root.join(day).join(name))
+            .find(|dir| dir.is_dir())
+    }
+
+    /// Lists the input names for a day across all roots: examples first, then everything else,
+    /// and reals last.
+    pub fn input_names(&self, day: &str) -> Vec<String> {
+        let mut names = self
+            .0
+            .iter()
+            .flat_map(|root| input_names(&root.join(day)))
+            .collect::<Vec<_>>();
+        sort_input_names(&mut names);
+        names.dedup();
+        names
+    }
+}
+
+/// Inputs grouped by their names: `example*` inputs, `real*` inputs, or all of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Category {
+    Examples,
+    Reals,
+    #[default]
+    All,
+}
+
+impl Category {
+    pub fn includes(self, input: &str) -> bool {
+        match self {
+            Category::Examples => input.starts_with("example"),
+            Category::Reals => input.starts_with("real"),
+            Category::All => true,
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Category::Examples => "examples",
+            Category::Reals => "reals",
+            Category::All => "all",
+        })
+    }
+}
+
+/// Which of each day's inputs to load. An input is loaded if it's in the category, matches any
+/// of the `only` globs, or all inputs if there are none, and matches none of the `skip` globs.
+#[derive(Debug, Clone, Default)]
+pub struct InputFilter {
+    /// A single input to load by its exact name.
+    pub name: Option<String>,
+    pub only: Vec<String>,
+    pub skip: Vec<String>,
+    pub category: Category,
+}
+
+impl InputFilter {
+    pub fn matches(&self, input: &str) -> bool {
+        self.name.as_ref().is_none_or(|name| name == input)
+            && self.category.includes(input)
+            && (self.only.is_empty() || self.only.iter().any(|g| glob_match(g, input)))
+            && !self.skip.iter().any(|g| glob_match(g, input))
+    }
+}
+
+/// Matches `text` against a glob where `*` matches any run of characters and `?` any single
+/// character.
+pub fn glob_match(glob: &str, text: &str) -> bool {
+    let (glob, text) = (
+        glob.chars().collect::<Vec<_>>(),
+        text.chars().collect::<Vec<_>>(),
+    );
+    let (mut g, mut t) = (0, 0);
+    // Where to resume after the last `*` if the rest doesn't match: the glob just after it, and
+    // the text one further on than last time.
+    let mut backtrack = None::<(usize, usize)>;
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g + 1, t));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((after_star, from)) => {
+                    backtrack = Some((after_star, from + 1));
+                    g = after_star;
+                    t = from + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+/// Loads the inputs of the selected days that pass `filter`.
+pub fn load_cases(
+    roots: &InputRoots,
+    day_selector: &str,
+    filter: &InputFilter,
+) -> Result<Vec<Case>, Error> {
+    let days = select_days(day_selector)?;
+    let is_single_day = days.len() == 1;
+
+    let mut cases = Vec::<Case>::new();
+    for (day, f) in days {
+        let load = |input: &str| -> Option<Case> {
+            let dir = roots.input_dir(day, input)?;
+            let path = |file_name: &str| -> PathBuf { dir.join(format!("{file_name}.txt")) };
+            // A bad params file is reported when the case runs, like an unreadable input.
+            let (params, params_error) = match Params::load(&dir) {
+                Ok(params) => (params, None),
+                Err(e) => (Params::default(), Some(e)),
+            };
+
+            Some(Case {
+                day,
+                f,
+                name: input.to_owned(),
+                input: match params_error {
+                    Some(e) => Err(e),
+                    None => std::fs::read_to_string(path("input"))
+                        .map_err(|e| format!("couldn't read {}: {e}", path("input").display())),
+                },
+                params,
+                part1: std::fs::read_to_string(path("part1")).ok(),
+                part2: std::fs::read_to_string(path("part2")).ok(),
+                dir: Some(dir.clone()),
+            })
+        };
+
+        let mut input_names = roots.input_names(day);
+        if let Some(input) = &filter.name {
+            if is_single_day && !input_names.contains(input) {
+                return Err(Error::Usage(format!("Unknown input {input} for {day}")));
+            }
+        }
+        input_names.retain(|name| filter.matches(name));
+
+        cases.extend(input_names.iter().filter_map(|input| load(input)));
+    }
+    if cases.is_empty() {
+        let roots = roots
+            .0
+            .iter()
+            .map(|r| r.display().to_string())
+            .collect::<Vec<_>>();
+        return Err(Error::Usage(format!(
+            "No inputs for {day_selector} under {}{}",
+            roots.join(", "),
+            match filter.only.is_empty()
+                && filter.skip.is_empty()
+                && filter.category == Category::All
+            {
+                true => "",
+                false => " that pass the input filters",
+            }
+        )));
+    }
+    Ok(cases)
+}
+
+/// Builds a case from a puzzle input outside the `inputs/` tree: the file at `path`, or stdin if
+/// `path` is `None`. The expected answers come from the caller rather than expectation files.
+pub fn adhoc_case(
+    day_selector: &str,
+    path: Option<&Path>,
+    part1: Option<String>,
+    part2: Option<String>,
+) -> Result<Case, Error> {
+    let (day, f) = match select_days(day_selector)?.as_slice() {
+        [day] => *day,
+        _ => {
+            return Err(Error::Usage(format!(
+                "{day_selector} names more than one day, but an input file is for a single day"
+            )))
+        }
+    };
+    let (name, input) = match path {
+        Some(path) => (
+            path.display().to_string(),
+            std::fs::read_to_string(path)
+                .map_err(|e| Error::Io(format!("Couldn't read {}: {e}", path.display())))?,
+        ),
+        None => (
+            "stdin".to_owned(),
+            std::io::read_to_string(std::io::stdin())
+                .map_err(|e| Error::Io(format!("Couldn't read stdin: {e}")))?,
+        ),
+    };
+    Ok(Case {
+        day,
+        f,
+        name,
+        dir: None,
+        input: Ok(input),
+        params: Params::default(),
+        part1,
+        part2,
+    })
+}
+
+/// Resolves a day selector to the matching registered days, in registry order.
+pub fn select_days(selector: &str) -> Result<Vec<(&'static str, DayFn)>, Error> {
+    let unknown = || Error::Usage(format!("Unknown name {selector}"));
+    let day_num =
+        |s: &str| -> Option<usize> { s.strip_prefix("day").unwrap_or(s).parse::<usize>().ok() };
+
+    let all = DAYS.iter().map(|(name, f)| (*name, *f));
+    let days = if selector == "all" {
+        all.collect::<Vec<_>>()
+    } else if let Some((from, to)) = selector.split_once('-') {
+        let range = day_num(from).ok_or_else(unknown)?..=day_num(to).ok_or_else(unknown)?;
+        all.filter(|(name, _)| day_num(name).is_some_and(|n| range.contains(&n)))
+            .collect()
+    } else {
+        let num = day_num(selector).ok_or_else(unknown)?;
+        all.filter(|(name, _)| day_num(name) == Some(num)).collect()
+    };
+
+    if days.is_empty() {
+        return Err(unknown());
+    }
+    Ok(days)
+}
+
+/// Lists the input directory names in one day's directory, sorted like
+/// `InputRoots::input_names`.
+pub fn input_names(all_inputs_dir: &Path) -> Vec<String> {
+    let mut dir_names = match std::fs::read_dir(all_inputs_dir) {
+        Ok(dirs) => dirs
+            .filter_map(|dir| {
+                let dir = dir.unwrap();
+                match dir.file_type().unwrap().is_dir() {
+                    true => Some(dir.file_name().to_str().unwrap().to_owned()),
+                    false => None,
+                }
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => return vec![],
+    };
+
+    sort_input_names(&mut dir_names);
+    dir_names
+}
+
+/// Sorts input names with examples first, then everything else, and reals last.
+pub fn sort_input_names(dir_names: &mut [String]) {
+    dir_names.sort();
+    dir_names.sort_by_key(|dir_name| {
+        if dir_name.starts_with("example") {
+            0
+        } else if !dir_name.starts_with("real") {
+            1
+        } else {
+            2
+        }
+    });
+}
+
+/// What came of running a solver once, before it's compared with the expected answers.
+struct Execution {
+    output: DayOutput,
+    duration: Duration,
+    error: Option<DayError>,
+    crash: Option<Crash>,
+}
+
+fn execute(
+    f: DayFn,
+    input: &Result<String, String>,
+    params: &Params,
+    cancel: CancelToken,
+    progress: Option<Progress>,
+) -> Execution {
+    let mut output = DayOutput::with_cancel(cancel).with_params(params.clone());
+    if let Some(progress) = progress {
+        output = output.with_progress(progress);
+    }
+    let before = Instant::now();
+    let (error, crash) = match input {
+        Ok(input) => match catch_crash(|| f(input, &mut output)) {
+            Ok(r) => (r.err(), None),
+            Err(crash) => (None, Some(crash)),
+        },
+        Err(message) => (
+            None,
+            Some(Crash {
+                message: message.clone(),
+                location: None,
+            }),
+        ),
+    };
+    let after = Instant::now();
+    let duration = match input {
+        Ok(_) => after - before,
+        Err(_) => Duration::ZERO,
+    };
+
+    Execution {
+        output,
+        duration,
+        error,
+        crash,
+    }
+}
+
+thread_local! {
+    /// Whether this thread is inside `catch_crash`, so the panic hook knows to record the panic
+    /// rather than print it.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static LAST_CRASH: RefCell<Option<Crash>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Runs `f`, turning a panic into a `Crash` with its message and location. Panics outside
+/// `catch_crash` are printed as usual.
+fn catch_crash<T>(f: impl FnOnce() -> T) -> Result<T, Crash> {
+    INSTALL_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.get() {
+                return default_hook(info);
+            }
+            let crash = Crash {
+                message: panic_message(info.payload()),
+                location: info.location().map(|l| l.to_string()),
+            };
+            LAST_CRASH.set(Some(crash));
+        }));
+    });
+
+    CATCHING.set(true);
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(false);
+    res.map_err(|payload| {
+        LAST_CRASH.take().unwrap_or_else(|| Crash {
+            message: panic_message(payload.as_ref()),
+            location: None,
+        })
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panic with a non-string payload".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_cases() {
+        let cases = [
+            ("", "", true),
+            ("", "a", false),
+            ("example", "example", true),
+            ("example", "example2", false),
+            ("example", "exampl", false),
+            ("*", "", true),
+            ("*", "anything", true),
+            ("**", "x", true),
+            ("example*", "example", true),
+            ("example*", "example12", true),
+            ("example*", "real", false),
+            ("*2", "example2", true),
+            ("*2", "example21", false),
+            ("?", "", false),
+            ("?", "a", true),
+            ("?", "ab", false),
+            ("example?", "example2", true),
+            ("example?", "example", false),
+            ("ex*le?", "example3", true),
+            ("*a*b", "xaxxab", true),
+            ("*a*b", "xaxxa", false),
+            ("a*a*a", "aaa", true),
+            ("a*a*a", "aa", false),
+            ("*?", "", false),
+            ("*?", "z", true),
+            ("?*?", "ab", true),
+            ("réal*", "réal2", true),
+            // `?` is one character, not one byte.
+            ("r?al", "réal", true),
+            ("r??al", "réal", false),
+            ("r?al", "real", true),
+        ];
+        for (glob, text, expected) in cases {
+            assert_eq!(glob_match(glob, text), expected, "{glob:?} vs {text:?}");
+        }
+    }
+
+    #[test]
+    fn input_filter_combines_globs_and_category() {
+        let filter = InputFilter {
+            only: vec!["example*".to_owned(), "real".to_owned()],
+            skip: vec!["*2".to_owned()],
+            ..InputFilter::default()
+        };
+        let matched = ["example", "example2", "example3", "real", "real2", "other"]
+            .into_iter()
+            .filter(|i| filter.matches(i))
+            .collect::<Vec<_>>();
+        assert_eq!(matched, ["example", "example3", "real"]);
+
+        let filter = InputFilter {
+            name: Some("example2".to_owned()),
+            category: Category::Reals,
+            ..InputFilter::default()
+        };
+        assert!(!filter.matches("example2"));
+    }
+
+    /// Answers part 1, then ignores cancellation for longer than the harness waits for it.
+    fn stubborn(_input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+        output.part1(1)?;
+        output.diagnostic("stage", "part 2");
+        thread::sleep(CANCEL_GRACE * 3);
+        output.part2(2)?;
+        Ok(())
+    }
+
+    #[test]
+    fn timed_out_runs_keep_earlier_answers() {
+        let case = Case {
+            day: "day00",
+            f: stubborn,
+            name: "example".to_owned(),
+            dir: None,
+            input: Ok(String::new()),
+            params: Params::default(),
+            part1: Some("1".to_owned()),
+            part2: Some("2".to_owned()),
+        };
+        let report = case.run_with_timeout(Duration::from_millis(10));
+        assert_eq!(report.timeout, Some(Duration::from_millis(10)));
+        assert_eq!(report.parts[0].status, Status::Pass);
+        assert_eq!(report.parts[1].status, Status::Timeout);
+        assert_eq!(
+            report.diagnostics,
+            [("stage".to_owned(), "part 2".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! A record of every run's answers and timings, kept across commits.
+//!
+//! The store is a JSON lines file, by default `target/aoc24-history.jsonl`, with one line per day
+//! and input run:
+//!
+//! ```text
+//! {"schema":1,"recorded_at":1734048000,"commit":"357453a...","dirty":false,"profile":"release","run":{...}}
+//! ```
+//!
+//! `recorded_at` is in seconds since the Unix epoch, `commit` is `null` outside a git checkout,
+//! `dirty` says whether the checkout had uncommitted changes, and `run` is the run's report as
+//! printed by `--format json`.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{error::Error, json, report::RunReport};
+
+/// The cargo profile this binary was built with, e.g. `release` or `perf`.
+pub const PROFILE: &str = env!("AOC24_PROFILE");
+
+/// Where history is kept: `AOC24_HISTORY` if set, and otherwise under the crate's `target/`.
+pub fn default_path() -> PathBuf {
+    match std::env::var_os("AOC24_HISTORY") {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join("aoc24-history.jsonl"),
+    }
+}
+
+/// The commit checked out in the crate's directory, and whether there are uncommitted changes.
+/// `None` if git isn't available or this isn't a checkout.
+fn git_commit() -> Option<(String, bool)> {
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(env!("CARGO_MANIFEST_DIR"))
+            .args(args)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+    let commit = git(&["rev-parse", "HEAD"])?;
+    let dirty = git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty());
+    Some((commit, dirty))
+}
+
+/// Appends `reports` to the history file at `path`.
+pub fn record(path: &Path, reports: &[RunReport]) -> Result<(), Error> {
+    let recorded_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (commit, dirty) = match git_commit() {
+        Some((commit, dirty)) => (Some(commit), dirty),
+        None => (None, false),
+    };
+
+    let mut lines = String::new();
+    for report in reports {
+        lines.push_str(&json::object(&[
+            ("schema", "1".to_owned()),
+            ("recorded_at", recorded_at.to_string()),
+            ("commit", json::opt_string(&commit)),
+            ("dirty", dirty.to_string()),
+            ("profile", json::string(PROFILE)),
+            ("run", report.to_json()),
+        ]));
+        lines.push('\n');
+    }
+
+    let io_error = |e: std::io::Error| Error::Io(format!("Couldn't write {}: {e}", path.display()));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(lines.as_bytes()))
+        .map_err(io_error)
+}
+
+/// One recorded run, as read back from the history file.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub recorded_at: u64,
+    pub commit: Option<String>,
+    pub dirty: bool,
+    pub profile: String,
+    pub day: String,
+    pub input: String,
+    pub duration: Duration,
+    pub answers: [Option<String>; 2],
+}
+
+impl Entry {
+    fn from_json(line: &json::Value) -> Option<Entry> {
+        let run = line.get("run")?;
+        let text =
+            |v: &json::Value, key: &str| v.get(key).and_then(|v| v.as_str()).map(str::to_owned);
+        let answer = |part: &str| run.get(part).and_then(|p| text(p, "actual"));
+        Some(Entry {
+            recorded_at: line.get("recorded_at")?.as_u128()? as u64,
+            commit: text(line, "commit"),
+            dirty: line.get("dirty") == Some(&json::Value::Bool(true)),
+            profile: text(line, "profile")?,
+            day: text(run, "day")?,
+            input: text(run, "input")?,
+            duration: Duration::from_nanos(run.get("duration_ns")?.as_u128()? as u64),
+            answers: [answer("part1"), answer("part2")],
+        })
+    }
+
+    /// A short commit hash, marked with `*` if the checkout was dirty.
+    pub fn short_commit(&self) -> String {
+        match &self.commit {
+            Some(c) => format!(
+                "{}{}",
+                &c[..c.len().min(8)],
+                if self.dirty { "*" } else { "" }
+            ),
+            None => "-".to_owned(),
+        }
+    }
+}
+
+/// Reads every entry for `day` from the history file at `path`, oldest first. A missing file
+/// has no entries, and lines that can't be read, e.g. from a newer schema, are skipped.
+pub fn load(path: &Path, day: &str) -> Result<Vec<Entry>, Error> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(Error::Io(format!("Couldn't read {}: {e}", path.display()))),
+    };
+    Ok(text
+        .lines()
+        .filter_map(json::parse)
+        .filter(|line| line.get("schema") == Some(&json::Value::Number("1".to_owned())))
+        .filter_map(|line| Entry::from_json(&line))
+        .filter(|e| e.day == day)
+        .collect())
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time, e.g. `2024-12-13 09:30`.
+pub fn format_time(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let (hour, minute) = ((secs % 86400) / 3600, (secs % 3600) / 60);
+
+    // Howard Hinnant's days-to-civil algorithm.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}")
+}
+
+/// A horizontal bar `width` characters long at most, in proportion to `value / max`.
+pub fn bar(value: Duration, max: Duration, width: usize) -> String {
+    let len = match max.is_zero() {
+        true => 0,
+        false => ((value.as_secs_f64() / max.as_secs_f64()) * width as f64).round() as usize,
+    };
+    "#".repeat(len.clamp(1, width))
+}
@@ -0,0 +1,386 @@
+//! Just enough JSON for the harness's machine-readable output, and for reading it back.
+
+use std::{fmt::Write, iter::Peekable, str::Chars};
+
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn opt_string(s: &Option<String>) -> String {
+    match s {
+        Some(s) => string(s),
+        None => "null".to_owned(),
+    }
+}
+
+/// Writes an object from already-encoded values, keeping fields in the given order.
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields = fields
+        .iter()
+        .map(|(k, v)| format!("{}:{v}", string(k)))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// A parsed JSON value. Numbers keep their original text, since durations in nanoseconds don't
+/// all fit in an `f64`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a single JSON value, such as one line of `--format json` output. Returns `None` if the
+/// text isn't valid JSON.
+pub fn parse(text: &str) -> Option<Value> {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars)?;
+    skip_whitespace(&mut chars);
+    match chars.next() {
+        Some(_) => None,
+        None => Some(value),
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+}
+
+fn expect_word(chars: &mut Peekable<Chars>, word: &str, value: Value) -> Option<Value> {
+    word.chars()
+        .all(|w| chars.next() == Some(w))
+        .then_some(value)
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Option<Value> {
+    skip_whitespace(chars);
+    match *chars.peek()? {
+        'n' => expect_word(chars, "null", Value::Null),
+        't' => expect_word(chars, "true", Value::Bool(true)),
+        'f' => expect_word(chars, "false", Value::Bool(false)),
+        '"' => parse_string(chars).map(Value::String),
+        '[' => {
+            chars.next();
+            let mut items = Vec::<Value>::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Some(Value::Array(items));
+            }
+            loop {
+                items.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    ']' => return Some(Value::Array(items)),
+                    _ => return None,
+                }
+            }
+        }
+        '{' => {
+            chars.next();
+            let mut fields = Vec::<(String, Value)>::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Some(Value::Object(fields));
+            }
+            loop {
+                skip_whitespace(chars);
+                let key = parse_string(chars)?;
+                skip_whitespace(chars);
+                if chars.next()? != ':' {
+                    return None;
+                }
+                fields.push((key, parse_value(chars)?));
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    '}' => return Some(Value::Object(fields)),
+                    _ => return None,
+                }
+            }
+        }
+        '-' | '0'..='9' => parse_number(chars).map(Value::Number),
+        _ => None,
+    }
+}
+
+/// Takes a number in JSON's grammar: an optional minus, an integer part without leading zeros,
+/// then an optional fraction and exponent, each needing at least one digit.
+fn parse_number(chars: &mut Peekable<Chars>) -> Option<String> {
+    fn digits(chars: &mut Peekable<Chars>, number: &mut String) -> Option<()> {
+        let len = number.len();
+        while let Some(c) = chars.next_if(char::is_ascii_digit) {
+            number.push(c);
+        }
+        (number.len() > len).then_some(())
+    }
+
+    let mut number = String::new();
+    number.extend(chars.next_if_eq(&'-'));
+    match chars.next_if_eq(&'0') {
+        Some(zero) => number.push(zero),
+        None => digits(chars, &mut number)?,
+    }
+    if let Some(point) = chars.next_if_eq(&'.') {
+        number.push(point);
+        digits(chars, &mut number)?;
+    }
+    if let Some(e) = chars.next_if(|c| matches!(c, 'e' | 'E')) {
+        number.push(e);
+        number.extend(chars.next_if(|c| matches!(c, '+' | '-')));
+        digits(chars, &mut number)?;
+    }
+    Some(number)
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+    let mut out = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                '"' => out.push('"'),
+                '\\' => out.push('\\'),
+                '/' => out.push('/'),
+                'b' => out.push('\u{8}'),
+                'f' => out.push('\u{c}'),
+                'n' => out.push('\n'),
+                'r' => out.push('\r'),
+                't' => out.push('\t'),
+                'u' => {
+                    let hex = (0..4).map(|_| chars.next()).collect::<Option<String>>()?;
+                    out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                _ => return None,
+            },
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        day_output::Timings,
+        error::Crash,
+        report::{PartReport, RunReport, Status},
+    };
+
+    fn number(n: &str) -> Value {
+        Value::Number(n.to_owned())
+    }
+
+    #[test]
+    fn round_trips_run_reports() {
+        let report = RunReport {
+            day: "day05".to_owned(),
+            input: "example \"two\"".to_owned(),
+            duration: Duration::from_nanos(18_446_744_073_709_551_615),
+            timings: Timings {
+                parse: Some(Duration::from_nanos(4120)),
+                part1: None,
+                part2: Some(Duration::from_nanos(2675)),
+            },
+            parts: [
+                PartReport::new(Some(&"a\nb".into()), &Some("a\nb".to_owned())),
+                PartReport::new(None, &None),
+            ],
+            diagnostics: vec![("grid".to_owned(), "#.\t#\r\n\u{1}\\".to_owned())],
+            error: None,
+            crash: Some(Crash {
+                message: "attempt to subtract with overflow".to_owned(),
+                location: None,
+            }),
+            timeout: Some(Duration::from_millis(1500)),
+        };
+        let value = parse(&report.to_json()).unwrap();
+
+        assert_eq!(value.get("schema"), Some(&number("1")));
+        assert_eq!(
+            value.get("input").and_then(Value::as_str),
+            Some("example \"two\"")
+        );
+        assert_eq!(
+            value.get("duration_ns").and_then(Value::as_u128),
+            Some(18_446_744_073_709_551_615)
+        );
+        assert_eq!(value.get("parse_ns"), Some(&number("4120")));
+        assert_eq!(value.get("part1_ns"), Some(&Value::Null));
+        let part1 = value.get("part1").unwrap();
+        assert_eq!(
+            part1.get("status").and_then(Value::as_str),
+            Some(Status::Pass.label())
+        );
+        assert_eq!(part1.get("actual").and_then(Value::as_str), Some("a\nb"));
+        assert_eq!(
+            value.get("part2").unwrap().get("actual"),
+            Some(&Value::Null)
+        );
+        assert_eq!(
+            value
+                .get("diagnostics")
+                .unwrap()
+                .get("grid")
+                .and_then(Value::as_str),
+            Some("#.\t#\r\n\u{1}\\")
+        );
+        assert_eq!(value.get("error"), Some(&Value::Null));
+        assert_eq!(
+            value.get("crash").unwrap().get("location"),
+            Some(&Value::Null)
+        );
+        assert_eq!(value.get("timeout_ns"), Some(&number("1500000000")));
+    }
+
+    #[test]
+    fn round_trips_strings() {
+        for s in [
+            "",
+            "plain",
+            "quote \" and \\",
+            "\n\r\t",
+            "\u{0}\u{1f}",
+            "snow ☃",
+        ] {
+            assert_eq!(
+                parse(&string(s)),
+                Some(Value::String(s.to_owned())),
+                "{s:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let cases = [
+            (r#""\/""#, "/"),
+            (r#""\b\f""#, "\u{8}\u{c}"),
+            (r#""\u0041\u00e9""#, "Aé"),
+            (r#""\u263A""#, "☺"),
+        ];
+        for (json, expected) in cases {
+            assert_eq!(
+                parse(json),
+                Some(Value::String(expected.to_owned())),
+                "{json}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_numbers() {
+        for n in [
+            "0",
+            "-0",
+            "7",
+            "-12",
+            "3.25",
+            "1e9",
+            "1E+2",
+            "-2.5e-3",
+            "340282366920938463463374607431768211455",
+        ] {
+            assert_eq!(parse(n), Some(number(n)), "{n}");
+        }
+    }
+
+    #[test]
+    fn parses_nested_values() {
+        assert_eq!(
+            parse(r#" { "a" : [ 1 , true , null , { } , [ ] ] , "b" : false } "#),
+            Some(Value::Object(vec![
+                (
+                    "a".to_owned(),
+                    Value::Array(vec![
+                        number("1"),
+                        Value::Bool(true),
+                        Value::Null,
+                        Value::Object(vec![]),
+                        Value::Array(vec![]),
+                    ])
+                ),
+                ("b".to_owned(), Value::Bool(false)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let cases = [
+            "",
+            "nul",
+            "truth",
+            "-",
+            "01",
+            "1.",
+            ".5",
+            "1e",
+            "1e+",
+            "1-2",
+            "--1",
+            "1.2.3",
+            "+1",
+            r#""unterminated"#,
+            r#""bad \q escape""#,
+            r#""\u12""#,
+            r#""\ud800""#,
+            "[1,]",
+            "[1 2]",
+            r#"{"a" 1}"#,
+            r#"{"a":1,}"#,
+            "{a:1}",
+            "[1]]",
+            "1 2",
+        ];
+        for json in cases {
+            assert_eq!(parse(json), None, "{json:?}");
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Advent of Code 2024 solvers, plus the harness that runs them against the `inputs/` tree.
+//!
+//! Solvers are looked up by day with [`solver`] and run on puzzle text with [`solve`]. The
+//! harness modules build on the same registry to load inputs, compare expected answers and
+//! report on runs.
+
+pub mod answer;
+pub mod bench;
+pub mod day_output;
+pub mod days;
+pub mod direction;
+pub mod error;
+pub mod facing;
+pub mod fxhash;
+pub mod harness;
+pub mod history;
+mod json;
+pub mod map;
+pub mod params;
+pub mod parse;
+pub mod point;
+pub mod regions;
+pub mod report;
+pub mod scaffold;
+pub mod search;
+pub mod watch;
+
+pub use answer::Answer;
+pub use day_output::{DayError, DayOutput};
+pub use days::{DayFn, DAYS};
+pub use params::Params;
+
+/// Everything a solver reported, plus the error it stopped with, if any.
+pub struct Solution {
+    pub output: DayOutput,
+    pub error: Option<DayError>,
+}
+
+/// Looks up a solver by name, e.g. `day07`, or by number, e.g. `7`.
+pub fn solver(day: &str) -> Option<DayFn> {
+    harness::select_days(day)
+        .ok()
+        .filter(|days| days.len() == 1)
+        .map(|days| days[0].1)
+}
+
+/// Runs the solver for `day` on puzzle input text. Returns `None` if there is no such day.
+pub fn solve(day: &str, input: &str) -> Option<Solution> {
+    solve_with_params(day, input, Params::default())
+}
+
+/// Like [`solve`], for an input with parameters such as a `params.toml`.
+pub fn solve_with_params(day: &str, input: &str, params: Params) -> Option<Solution> {
+    let f = solver(day)?;
+    let mut output = DayOutput::new().with_params(params);
+    let error = f(input, &mut output).err();
+    Some(Solution { output, error })
+}
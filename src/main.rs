@@ -1,132 +1,578 @@
-mod day_output;
-mod days;
-pub mod direction;
-pub mod facing;
-pub mod map;
-
-use days::DAYS;
-
-use clap::Parser;
-use day_output::DayOutput;
+use aoc24::{
+    bench::{self, Baseline, Budget},
+    error::{Error, Failure},
+    harness::{self, Case, Category, InputFilter, InputRoots},
+    history,
+    report::{self, RunReport, Status},
+    scaffold,
+    watch::{self, WatchOptions},
+};
+use clap::{builder::RangedU64ValueParser, Args, Parser, Subcommand, ValueEnum};
 use std::{
     path::{Path, PathBuf},
-    time::Instant,
+    process::ExitCode,
+    time::Duration,
 };
 
 #[derive(Parser, Debug)]
-struct Args {
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Runs the selected days repeatedly and reports timing statistics.
+    Bench(BenchArgs),
+    /// Adds a solver for a new day from a template, registers it, and creates an empty example
+    /// input for it.
+    New(NewArgs),
+    /// Creates a new input case for a day, with its expected answers.
+    AddCase(AddCaseArgs),
+    /// Shows a day's recorded timings over time, and where its answers changed.
+    History(HistoryArgs),
+    /// Runs the selected days, then re-runs them whenever their inputs or the solver sources
+    /// change, rebuilding as needed.
+    Watch(WatchArgs),
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Day to run, e.g. `day07`. Also accepts `all`, or a range such as `1-25` or `day03-day07`.
+    #[arg(required = true)]
+    day: Option<String>,
+    /// Input directory name under `inputs/<day>/`, or `-` to read the puzzle input from stdin.
+    input: Option<String>,
+    #[command(flatten)]
+    filter: FilterArgs,
+    /// Load inputs from this directory instead of `./inputs`. May be repeated to layer roots, in
+    /// which case earlier roots take precedence, followed by any in `AOC24_INPUTS`.
+    #[arg(long)]
+    inputs_dir: Vec<PathBuf>,
+    /// Read the puzzle input from this file instead of the `inputs/` tree. `-` means stdin.
+    #[arg(long, conflicts_with = "input")]
+    file: Option<PathBuf>,
+    /// Expected part 1 answer, when running on `--file` or stdin.
+    #[arg(long)]
+    expect1: Option<String>,
+    /// Expected part 2 answer, when running on `--file` or stdin.
+    #[arg(long)]
+    expect2: Option<String>,
+    /// Set a solver parameter, overriding any from the input's `params.toml`. May be repeated.
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_param)]
+    param: Vec<(String, String)>,
+    /// Write each answer that has no expectation file into a new `part1.txt` or `part2.txt`.
+    #[arg(long)]
+    bless: bool,
+    /// Like `--bless`, but also offer to replace expectation files that disagree with the
+    /// answer, showing the difference and asking first.
+    #[arg(long)]
+    overwrite: bool,
+    /// Don't append this run's results to the history file. See the `history` command.
+    #[arg(long)]
+    no_history: bool,
+    /// Output format. `json` prints one JSON object per line for each day and input run, with
+    /// the schema documented on `RunReport::to_json`.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Give up on each day and input after this many seconds, reporting it as TIMEOUT.
+    #[arg(long, value_parser = parse_positive_secs)]
+    timeout: Option<Duration>,
+    /// Run up to this many days and inputs at once. The report is printed in the same order
+    /// either way, but timings are noisier when inputs compete for cores.
+    #[arg(long, short, default_value_t = 1, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    jobs: usize,
+}
+
+#[derive(Args, Debug)]
+struct FilterArgs {
+    /// Only run inputs whose names match this glob, e.g. `example*`. May be repeated to run
+    /// inputs that match any of them.
+    #[arg(long, value_name = "GLOB")]
+    only: Vec<String>,
+    /// Don't run inputs whose names match this glob. May be repeated.
+    #[arg(long, value_name = "GLOB")]
+    skip: Vec<String>,
+    /// Only run `example*` inputs, or only `real*` inputs.
+    #[arg(long, value_enum, default_value_t = Category::All)]
+    category: Category,
+}
+
+impl FilterArgs {
+    fn filter(&self, input: Option<&str>) -> InputFilter {
+        InputFilter {
+            name: input.map(str::to_owned),
+            only: self.only.clone(),
+            skip: self.skip.clone(),
+            category: self.category,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Args, Debug)]
+struct BenchArgs {
+    /// Day to benchmark, with the same syntax as for a normal run.
     day: String,
     input: Option<String>,
+    #[command(flatten)]
+    filter: FilterArgs,
+    /// Load inputs from this directory, as for a normal run.
+    #[arg(long)]
+    inputs_dir: Vec<PathBuf>,
+    /// Untimed runs of each input before measuring.
+    #[arg(long, default_value_t = 3)]
+    warmup: usize,
+    /// Timed runs of each input.
+    #[arg(long, default_value_t = 20, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    iters: usize,
+    /// Keep running each input until this many seconds have been spent on it, instead of a fixed
+    /// number of iterations.
+    #[arg(long, value_parser = parse_positive_secs)]
+    time_budget: Option<Duration>,
+    /// Give up on an input if any run of it takes longer than this many seconds, reporting it as
+    /// TIMEOUT and moving on to the next.
+    #[arg(long, value_parser = parse_positive_secs)]
+    timeout: Option<Duration>,
+    /// Write the medians to this file, for a later `--baseline` comparison.
+    #[arg(long)]
+    save_baseline: Option<PathBuf>,
+    /// Report each input's median against one saved earlier with `--save-baseline`.
+    #[arg(long)]
+    baseline: Option<PathBuf>,
 }
 
-fn main() -> Result<(), String> {
-    let args = Args::parse();
+#[derive(Args, Debug)]
+struct NewArgs {
+    /// Day to add, e.g. `day26`.
+    day: String,
+    /// Create the example input under this directory instead of the first inputs root.
+    #[arg(long)]
+    inputs_dir: Option<PathBuf>,
+}
 
-    let mut f: Option<&fn(&str, output: &mut DayOutput) -> ()> = None;
+#[derive(Args, Debug)]
+struct AddCaseArgs {
+    /// Day to add the case to, e.g. `day12`.
+    day: String,
+    /// Name of the new input directory, e.g. `example6`.
+    name: String,
+    /// File holding the puzzle input, e.g. pasted from the clipboard. Read from stdin if omitted.
+    #[arg(long)]
+    from_clipboard_file: Option<PathBuf>,
+    /// Expected part 1 answer.
+    #[arg(long)]
+    part1: Option<String>,
+    /// Expected part 2 answer.
+    #[arg(long)]
+    part2: Option<String>,
+    /// Create the case under this directory instead of the first inputs root.
+    #[arg(long)]
+    inputs_dir: Option<PathBuf>,
+}
 
-    for day in DAYS {
-        if day.0 == args.day {
-            f = Some(&day.1);
-            break;
+#[derive(Args, Debug)]
+struct HistoryArgs {
+    /// Day to show, e.g. `day22`.
+    day: String,
+    /// Only show this input.
+    input: Option<String>,
+    /// Only show runs built with this cargo profile, e.g. `release`.
+    #[arg(long)]
+    profile: Option<String>,
+    /// Show at most this many of the latest runs of each input.
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+}
+
+#[derive(Args, Debug)]
+struct WatchArgs {
+    /// Day to watch, with the same syntax as for a normal run.
+    day: String,
+    input: Option<String>,
+    #[command(flatten)]
+    filter: FilterArgs,
+    /// Load inputs from this directory, as for a normal run.
+    #[arg(long)]
+    inputs_dir: Vec<PathBuf>,
+    /// Give up on each day and input after this many seconds, as for a normal run.
+    #[arg(long, value_parser = parse_positive_secs)]
+    timeout: Option<Duration>,
+    /// Cargo profile to rebuild and run with.
+    #[arg(long, default_value = "release")]
+    profile: String,
+    /// Seconds between checks for changed files.
+    #[arg(long, value_parser = parse_positive_secs, default_value = "0.5")]
+    interval: Duration,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let res = match &cli.command {
+        Some(Command::Bench(args)) => bench(args),
+        Some(Command::New(args)) => new_day(args),
+        Some(Command::AddCase(args)) => add_case(args),
+        Some(Command::History(args)) => show_history(args),
+        Some(Command::Watch(args)) => watch::watch(&WatchOptions {
+            day: args.day.clone(),
+            filter: args.filter.filter(args.input.as_deref()),
+            inputs_dirs: args.inputs_dir.clone(),
+            timeout: args.timeout,
+            profile: args.profile.clone(),
+            interval: args.interval,
+        }),
+        None => run_days(&cli.run),
+    };
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            e.exit_code()
         }
     }
+}
 
-    let f = f.ok_or(format!("Unknown name {}", args.day))?;
-    let all_inputs_dir = format!("./inputs/{}", args.day);
-
-    let load_and_run = |input: &str| {
-        let path = |file_name: &str| -> PathBuf {
-            let path_name = format!("{}/{}/{}.txt", all_inputs_dir, input, file_name);
-            Path::new(&path_name).to_owned()
-        };
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((k, v)) => Ok((k.trim().to_owned(), v.trim().to_owned())),
+        None => Err("expected KEY=VALUE".to_owned()),
+    }
+}
 
-        let input_str = std::fs::read_to_string(path("input")).unwrap();
-        let part1 = std::fs::read_to_string(path("part1")).ok();
-        let part2 = std::fs::read_to_string(path("part2")).ok();
+fn parse_positive_secs(s: &str) -> Result<Duration, String> {
+    let secs = s.parse::<f64>().map_err(|e| e.to_string())?;
+    match Duration::try_from_secs_f64(secs).map_err(|e| e.to_string())? {
+        d if d.is_zero() => Err("must be more than zero seconds".to_owned()),
+        d => Ok(d),
+    }
+}
 
-        run(f, input, &input_str, &part1, &part2);
+fn run_days(args: &RunArgs) -> Result<(), Error> {
+    let day = args.day.as_deref().unwrap();
+    let adhoc = match (&args.file, args.input.as_deref()) {
+        (Some(path), _) if path.as_os_str() == "-" => Some(None),
+        (Some(path), _) => Some(Some(path.as_path())),
+        (None, Some("-")) => Some(None),
+        (None, _) => None,
+    };
+    let mut cases = match adhoc {
+        Some(path) => vec![harness::adhoc_case(
+            day,
+            path,
+            args.expect1.clone(),
+            args.expect2.clone(),
+        )?],
+        None if args.expect1.is_some() || args.expect2.is_some() => {
+            return Err(Error::Usage(
+                "--expect1 and --expect2 need --file or stdin input".to_owned(),
+            ))
+        }
+        None => harness::load_cases(
+            &InputRoots::new(&args.inputs_dir),
+            day,
+            &args.filter.filter(args.input.as_deref()),
+        )?,
     };
 
-    match &args.input {
-        Some(input) => load_and_run(input),
-        None => {
-            let mut dir_names = std::fs::read_dir(Path::new(&all_inputs_dir))
-                .unwrap()
-                .filter_map(|dir| {
-                    let dir = dir.unwrap();
-                    match dir.file_type().unwrap().is_dir() {
-                        true => Some(dir.file_name().to_str().unwrap().to_owned()),
-                        false => None,
+    for case in cases.iter_mut() {
+        for (k, v) in args.param.iter() {
+            case.params.set(k, v);
+        }
+    }
+
+    let mut reports = harness::run_cases(
+        &cases,
+        args.jobs,
+        args.timeout,
+        |case| {
+            if args.format == Format::Text {
+                println!("[{}/{}] Running...", case.day, case.name);
+            }
+        },
+        |report| match args.format {
+            Format::Text => report::print_run(report),
+            Format::Json => println!("{}", report.to_json()),
+        },
+    );
+
+    if args.bless || args.overwrite {
+        bless(&cases, &mut reports, args.overwrite)?;
+    }
+
+    if !args.no_history {
+        // Ad hoc inputs aren't worth tracking, since there's no telling one from another.
+        let tracked = cases
+            .iter()
+            .zip(reports.iter())
+            .filter(|(case, _)| case.dir.is_some())
+            .map(|(_, report)| report.clone())
+            .collect::<Vec<_>>();
+        if let Err(e) = history::record(&history::default_path(), &tracked) {
+            eprintln!("Not recording history: {e}");
+        }
+    }
+
+    if args.format == Format::Text {
+        report::print_summary(&reports);
+    }
+
+    let failures = reports
+        .iter()
+        .flat_map(|r| r.failures())
+        .collect::<Vec<_>>();
+    if !failures.is_empty() {
+        return Err(Error::Failed(failures));
+    }
+    Ok(())
+}
+
+/// Writes each answer that has no expectation file into one. With `overwrite`, also offers to
+/// replace expectation files that disagree with the answer. Parts written are marked as passing.
+/// Everything goes to stderr so as not to interleave with JSON output.
+fn bless(cases: &[Case], reports: &mut [RunReport], overwrite: bool) -> Result<(), Error> {
+    for (case, report) in cases.iter().zip(reports.iter_mut()) {
+        let Some(dir) = &case.dir else {
+            continue;
+        };
+        for (i, part) in report.parts.iter_mut().enumerate() {
+            let Some(actual) = &part.actual else {
+                continue;
+            };
+            let path = dir.join(format!("part{}.txt", i + 1));
+            match (part.status, &part.expected) {
+                (Status::NoExpected, _) => {}
+                (Status::Fail, Some(expected)) if overwrite => {
+                    eprintln!("--- {}", path.display());
+                    eprintln!("+++ {}/{} part {}", case.day, case.name, i + 1);
+                    for line in expected.lines() {
+                        eprintln!("-{line}");
+                    }
+                    for line in actual.lines() {
+                        eprintln!("+{line}");
+                    }
+                    if !confirm(&format!("Overwrite {}?", path.display()))? {
+                        continue;
                     }
-                })
-                .collect::<Vec<_>>();
-
-            dir_names.sort();
-            // Run examples first
-            dir_names.retain_mut(|dir_name| {
-                if dir_name.starts_with("example") {
-                    load_and_run(dir_name);
-                    return false;
-                }
-                true
-            });
-
-            // Run everything except reals second
-            dir_names.retain_mut(|dir_name| {
-                if !dir_name.starts_with("real") {
-                    load_and_run(dir_name);
-                    return false;
                 }
-                true
-            });
-
-            // Run everything remaining
-            for dir_name in dir_names.iter() {
-                load_and_run(dir_name);
+                _ => continue,
             }
+            std::fs::write(&path, actual)
+                .map_err(|e| Error::Io(format!("Couldn't write {}: {e}", path.display())))?;
+            eprintln!("Wrote {}", path.display());
+            part.status = Status::Pass;
+            part.expected = Some(actual.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Asks a yes or no question on stderr, defaulting to no.
+fn confirm(question: &str) -> Result<bool, Error> {
+    eprint!("{question} [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin()
+        .read_line(&mut answer)
+        .map_err(|e| Error::Io(format!("Couldn't read stdin: {e}")))?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn show_history(args: &HistoryArgs) -> Result<(), Error> {
+    let day = match harness::select_days(&args.day)?.as_slice() {
+        [(day, _)] => *day,
+        _ => {
+            return Err(Error::Usage(format!(
+                "{} names more than one day",
+                args.day
+            )))
         }
     };
+    let path = history::default_path();
+    let mut entries = history::load(&path, day)?;
+    entries.retain(|e| {
+        args.input.as_ref().is_none_or(|i| *i == e.input)
+            && args.profile.as_ref().is_none_or(|p| *p == e.profile)
+    });
+    if entries.is_empty() {
+        println!("No history for {day} in {}", path.display());
+        return Ok(());
+    }
+
+    let mut inputs = entries.iter().map(|e| e.input.clone()).collect::<Vec<_>>();
+    harness::sort_input_names(&mut inputs);
+    inputs.dedup();
+    for input in inputs {
+        let runs = entries
+            .iter()
+            .filter(|e| e.input == input)
+            .collect::<Vec<_>>();
+        let max = runs.iter().map(|e| e.duration).max().unwrap_or_default();
+        let rows = runs
+            .iter()
+            .enumerate()
+            .skip(runs.len().saturating_sub(args.limit))
+            .map(|(i, e)| {
+                // Flag answers that differ from the run before, even if that run isn't shown.
+                let changes = match i.checked_sub(1).map(|j| runs[j]) {
+                    Some(prev) => (0..2)
+                        .filter(|&p| prev.answers[p] != e.answers[p])
+                        .map(|p| {
+                            let was = prev.answers[p].as_deref().unwrap_or("-");
+                            match prev.commit == e.commit {
+                                true => format!("part {} changed from {was}", p + 1),
+                                false => format!(
+                                    "part {} changed from {was} at {}",
+                                    p + 1,
+                                    prev.short_commit()
+                                ),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    None => String::new(),
+                };
+                vec![
+                    history::format_time(e.recorded_at),
+                    e.short_commit(),
+                    e.profile.clone(),
+                    format!("{:#?}", e.duration),
+                    history::bar(e.duration, max, 30),
+                    e.answers[0].clone().unwrap_or_else(|| "-".to_owned()),
+                    e.answers[1].clone().unwrap_or_else(|| "-".to_owned()),
+                    changes,
+                ]
+            })
+            .collect::<Vec<_>>();
 
+        println!("[{day}/{input}]");
+        report::print_table(
+            &[
+                "recorded (UTC)",
+                "commit",
+                "profile",
+                "time",
+                "",
+                "part 1",
+                "part 2",
+                "changes",
+            ],
+            &rows,
+        );
+        println!();
+    }
     Ok(())
 }
 
-fn run(
-    f: &fn(&str, output: &mut DayOutput),
-    name: &str,
-    input: &str,
-    part1: &Option<String>,
-    part2: &Option<String>,
-) {
-    println!("[{name}] Running...");
+fn bench(args: &BenchArgs) -> Result<(), Error> {
+    let cases = harness::load_cases(
+        &InputRoots::new(&args.inputs_dir),
+        &args.day,
+        &args.filter.filter(args.input.as_deref()),
+    )?;
+    let baseline = match &args.baseline {
+        Some(path) => Some(Baseline::load(path)?),
+        None => None,
+    };
+    let budget = match args.time_budget {
+        Some(budget) => Budget::Time(budget),
+        None => Budget::Iters(args.iters),
+    };
+
+    let mut medians = Baseline::default();
+    let mut rows = Vec::<Vec<String>>::new();
+    let mut failures = Vec::<Failure>::new();
+    for case in cases.iter() {
+        println!("[{}/{}] Benchmarking...", case.day, case.name);
+        let stats = match bench::measure(case, args.warmup, budget, args.timeout) {
+            Ok(stats) => stats,
+            Err(stopped) => {
+                for failure in stopped.iter() {
+                    println!("    [{}] Skipped: {failure}", case.name);
+                }
+                failures.extend(stopped);
+                let mut row = vec![case.day.to_owned(), case.name.clone()];
+                row.resize(9, "-".to_owned());
+                rows.push(row);
+                continue;
+            }
+        };
+        medians.insert(case.day, &case.name, stats.median);
+        let vs_baseline = baseline
+            .as_ref()
+            .and_then(|b| b.get(case.day, &case.name))
+            .and_then(|base| bench::change_pct(base, stats.median))
+            .map(|pct| format!("{pct:+.1}%"))
+            .unwrap_or_else(|| "-".to_owned());
+        rows.push(vec![
+            case.day.to_owned(),
+            case.name.clone(),
+            stats.runs.to_string(),
+            format!("{:#?}", stats.min),
+            format!("{:#?}", stats.median),
+            format!("{:#?}", stats.mean),
+            format!("{:#?}", stats.p95),
+            format!("{:#?}", stats.stddev),
+            vs_baseline,
+        ]);
+    }
 
-    let mut res = DayOutput::new();
-    let before = Instant::now();
-    f(input, &mut res);
-    let after = Instant::now();
-    let dur = after - before;
-    println!("    [{name}] Took {dur:#?}");
+    println!();
+    report::print_table(
+        &[
+            "day", "input", "runs", "min", "median", "mean", "p95", "stddev", "baseline",
+        ],
+        &rows,
+    );
 
-    compare_result(name, 1, part1, res.get_part1());
-    compare_result(name, 2, part2, res.get_part2());
+    if let Some(path) = &args.save_baseline {
+        medians.save(path)?;
+    }
+    if !failures.is_empty() {
+        return Err(Error::Failed(failures));
+    }
+    Ok(())
 }
 
-fn compare_result(name: &str, part: i64, expected: &Option<String>, actual: &Option<String>) {
-    match (actual, expected) {
-        (None, None) => {
-            println!("    [{name}] Part {part}: No result nor expected result")
-        }
-        (None, Some(e)) => {
-            println!("    [{name}] Part {part}: No result, but expected {e}")
-        }
-        (Some(a), None) => {
-            println!("    [{name}] Part {part}: Result was {a}")
-        }
-        (Some(a), Some(e)) => {
-            if a == e {
-                println!("    [{name}] Part {part}: PASS Result was {a} as expected")
-            } else {
-                println!("    [{name}] Part {part}: FAIL Result was {a} but expected {e}")
-            }
-        }
+/// Where `new` and `add-case` write inputs: the given directory, or else the highest precedence
+/// inputs root.
+fn scaffold_root(inputs_dir: &Option<PathBuf>) -> PathBuf {
+    match inputs_dir {
+        Some(dir) => dir.clone(),
+        None => InputRoots::new(&[]).roots()[0].clone(),
+    }
+}
+
+fn new_day(args: &NewArgs) -> Result<(), Error> {
+    let src_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let paths = scaffold::new_day(&src_root, &scaffold_root(&args.inputs_dir), &args.day)?;
+    for path in paths {
+        println!("Wrote {}", path.display());
     }
+    Ok(())
+}
+
+fn add_case(args: &AddCaseArgs) -> Result<(), Error> {
+    let input = match &args.from_clipboard_file {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Couldn't read {}: {e}", path.display())))?,
+        None => std::io::read_to_string(std::io::stdin())
+            .map_err(|e| Error::Io(format!("Couldn't read stdin: {e}")))?,
+    };
+    let dir = scaffold::add_case(
+        &scaffold_root(&args.inputs_dir),
+        &args.day,
+        &args.name,
+        &input,
+        args.part1.as_deref(),
+        args.part2.as_deref(),
+    )?;
+    println!("Wrote {}", dir.display());
+    Ok(())
 }
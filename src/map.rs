@@ -1,27 +1,293 @@
-#[derive(Debug, Clone)]
-pub struct Row<T> {
-    pub tiles: Vec<T>,
-}
+use crate::{
+    parse::{Lines, ParseError},
+    point::Point,
+};
 
-#[derive(Debug, Clone)]
+/// A rectangular grid of tiles, addressed by `(x, y)` with `(0, 0)` at the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Map<T> {
-    pub rows: Vec<Row<T>>,
+    width: usize,
+    height: usize,
+    /// Row-major, so `(x, y)` is at `y * width + x`.
+    tiles: Vec<T>,
+}
+
+impl<T> Map<T> {
+    /// Builds a map by calling `f` for each position, in reading order.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Map<T> {
+        let mut tiles = Vec::with_capacity(width * height);
+        for y in 0..height {
+            tiles.extend((0..width).map(|x| f(Point::new(x as isize, y as isize))));
+        }
+        Map {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    /// Parses a map with one tile per character, from `lines` up to the next blank line or the
+    /// end of the input. The blank line is consumed. `tile` converts each character, or says what
+    /// it expected instead, and the positions of any characters in `markers` are recorded too,
+    /// e.g. a start `S` that's also an ordinary tile. Every row must be the same width.
+    pub fn parse(
+        lines: &mut Lines,
+        markers: &[char],
+        mut tile: impl FnMut(char) -> Result<T, &'static str>,
+    ) -> Result<(Map<T>, Markers), ParseError> {
+        let mut tiles = Vec::<T>::new();
+        let mut height = 0usize;
+        let mut found = Markers {
+            positions: vec![],
+            first_line: 0,
+            end_line: 0,
+        };
+        let mut width = None::<usize>;
+        for line in lines.by_ref() {
+            if line.text.is_empty() {
+                match height == 0 {
+                    true => return Err(line.error("a map row")),
+                    false => break,
+                }
+            }
+            if height == 0 {
+                found.first_line = line.number;
+            }
+            let y = height as isize;
+            let row_start = tiles.len();
+            for (x, c) in line.text.chars().enumerate() {
+                if width.is_some_and(|w| x >= w) {
+                    return Err(line.char_error(x, c, "the end of the row, as in the first row"));
+                }
+                tiles.push(tile(c).map_err(|expected| line.char_error(x, c, expected))?);
+                if markers.contains(&c) {
+                    found.positions.push((c, Point::new(x as isize, y)));
+                }
+            }
+            let row_width = tiles.len() - row_start;
+            match width {
+                Some(w) if row_width < w => {
+                    return Err(line.end_error(&format!("{w} tiles, as in the first row")))
+                }
+                Some(_) => {}
+                None => width = Some(row_width),
+            }
+            height += 1;
+            found.end_line = line.number + 1;
+        }
+        let Some(width) = width else {
+            return Err(lines.end_error("a map row"));
+        };
+        let map = Map {
+            width,
+            height,
+            tiles,
+        };
+        Ok((map, found))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The index of `p` in reading order, if it's on the map. Handy for keeping per-tile state
+    /// in a plain `Vec` or bitset alongside the map.
+    pub fn index(&self, p: Point) -> Option<usize> {
+        // Negative coordinates wrap to huge ones when cast, so one comparison per axis covers
+        // both ends.
+        let (x, y) = (p.x as usize, p.y as usize);
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    /// The position with index `idx` in reading order, if it's on the map. The inverse of
+    /// `index`.
+    pub fn point(&self, idx: usize) -> Option<Point> {
+        // Checking the index first also keeps a zero-width map from dividing by zero.
+        (idx < self.tiles.len())
+            .then(|| Point::new((idx % self.width) as isize, (idx / self.width) as isize))
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        self.index(pos).map(|i| &self.tiles[i])
+    }
+
+    pub fn at_mut(&mut self, pos: Point) -> Option<&mut T> {
+        self.index(pos).map(|i| &mut self.tiles[i])
+    }
+
+    /// Every position on the map, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height)
+            .flat_map(move |y| (0..width).map(move |x| Point::new(x as isize, y as isize)))
+    }
+
+    /// Every tile with its position, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.positions().zip(self.tiles.iter())
+    }
+
+    /// The position of the first tile, in reading order, that `pred` accepts.
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, t)| pred(t)).map(|(pos, _)| pos)
+    }
+
+    /// Each row's tiles, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| &self.tiles[y * self.width..(y + 1) * self.width])
+    }
+
+    /// A map of the same size with `f` applied to every tile.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Map<U> {
+        Map {
+            width: self.width,
+            height: self.height,
+            tiles: self.tiles.iter().map(f).collect(),
+        }
+    }
 }
 
 impl<T: Copy> Map<T> {
-    pub fn at(&self, (x, y): (isize, isize)) -> Option<T> {
-        let x: usize = x.try_into().ok()?;
-        let y: usize = y.try_into().ok()?;
-        let row = self.rows.get(y)?;
-        row.tiles.get(x).copied()
+    pub fn at(&self, pos: Point) -> Option<T> {
+        self.get(pos).copied()
     }
 }
 
-impl<T> Map<T> {
-    pub fn at_mut(&mut self, (x, y): (isize, isize)) -> Option<&mut T> {
-        let x: usize = x.try_into().ok()?;
-        let y: usize = y.try_into().ok()?;
-        let row = self.rows.get_mut(y)?;
-        row.tiles.get_mut(x)
+/// Where the marker characters were found by `Map::parse`, in reading order.
+#[derive(Debug, Clone)]
+pub struct Markers {
+    positions: Vec<(char, Point)>,
+    /// Line numbers of the map's first row and of the line just after its last.
+    first_line: usize,
+    end_line: usize,
+}
+
+impl Markers {
+    /// The position of the one `marker` in the map. `expected` describes it, for the error if
+    /// there isn't one, e.g. "a start 'S' in the maze".
+    pub fn one(&self, marker: char, expected: &str) -> Result<Point, ParseError> {
+        let mut all = self.all(marker);
+        let pos = all.next().ok_or_else(|| ParseError {
+            line: self.end_line,
+            column: 1,
+            found: String::new(),
+            expected: expected.to_owned(),
+        })?;
+        match all.next() {
+            Some(p) => Err(ParseError {
+                line: self.first_line + p.y as usize,
+                column: p.x as usize + 1,
+                found: marker.to_string(),
+                expected: format!("only one {marker:?} in the map"),
+            }),
+            None => Ok(pos),
+        }
+    }
+
+    /// The positions of every `marker` in the map.
+    pub fn all(&self, marker: char) -> impl Iterator<Item = Point> + '_ {
+        self.positions
+            .iter()
+            .filter(move |(c, _)| *c == marker)
+            .map(|(_, pos)| *pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn tile(c: char) -> Result<char, &'static str> {
+        match c {
+            '.' | '#' | 'S' | 'E' => Ok(c),
+            _ => Err("a tile"),
+        }
+    }
+
+    fn parse(text: &str) -> Result<(Map<char>, Markers), ParseError> {
+        Map::parse(&mut parse::lines(text), &['S', 'E'], tile)
+    }
+
+    fn error(line: usize, column: usize, found: &str, expected: &str) -> ParseError {
+        ParseError {
+            line,
+            column,
+            found: found.to_owned(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    #[test]
+    fn parses_tiles_and_markers() {
+        let (map, markers) = parse("S.#\n#.E").unwrap();
+        assert_eq!((map.width(), map.height()), (3, 2));
+        let rows = map.rows().map(|r| r.iter().collect::<String>());
+        assert_eq!(rows.collect::<Vec<_>>(), ["S.#", "#.E"]);
+        assert_eq!(markers.one('S', "a start"), Ok(Point::new(0, 0)));
+        assert_eq!(markers.one('E', "an end"), Ok(Point::new(2, 1)));
+    }
+
+    #[test]
+    fn consumes_the_blank_line_after_the_map() {
+        let mut lines = parse::lines("S.\n.E\n\nmore");
+        let (map, _) = Map::parse(&mut lines, &[], tile).unwrap();
+        assert_eq!(map.height(), 2);
+        let next = lines.next().unwrap();
+        assert_eq!((next.number, next.text), (4, "more"));
+    }
+
+    #[test]
+    fn rejects_bad_tiles() {
+        assert_eq!(parse("..\n.x").unwrap_err(), error(2, 2, "x", "a tile"));
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let long = "the end of the row, as in the first row";
+        assert_eq!(parse("...\n....").unwrap_err(), error(2, 4, ".", long));
+        let short = "3 tiles, as in the first row";
+        assert_eq!(parse("...\n...\n..").unwrap_err(), error(3, 3, "", short));
+    }
+
+    #[test]
+    fn rejects_missing_maps() {
+        assert_eq!(parse("\n...").unwrap_err(), error(1, 1, "", "a map row"));
+        assert_eq!(parse("").unwrap_err(), error(1, 1, "", "a map row"));
+    }
+
+    #[test]
+    fn reports_missing_and_duplicate_markers() {
+        let mut lines = parse::lines("x\n..\n.S\n\n");
+        lines.next();
+        let (_, markers) = Map::parse(&mut lines, &['S', 'E'], tile).unwrap();
+        // A missing marker is reported just after the map, which started on line 2.
+        assert_eq!(markers.one('E', "an end"), Err(error(4, 1, "", "an end")));
+
+        let mut lines = parse::lines("x\nS.\n.S");
+        lines.next();
+        let (_, markers) = Map::parse(&mut lines, &['S'], tile).unwrap();
+        let only_one = "only one 'S' in the map";
+        assert_eq!(markers.one('S', "a start"), Err(error(3, 2, "S", only_one)));
+        assert_eq!(markers.all('S').count(), 2);
+    }
+
+    #[test]
+    fn converts_between_points_and_indices() {
+        let map = Map::from_fn(3, 2, |_| ());
+        for (idx, p) in map.positions().enumerate() {
+            assert_eq!(map.index(p), Some(idx));
+            assert_eq!(map.point(idx), Some(p));
+        }
+        assert_eq!(map.point(6), None);
+        for p in [(-1, 0), (0, -1), (3, 0), (0, 2), (-1, -1)] {
+            assert_eq!(map.index(Point::from(p)), None, "{p:?}");
+        }
+        let empty = Map::from_fn(0, 0, |_| ());
+        assert_eq!(empty.point(0), None);
     }
 }
@@ -0,0 +1,264 @@
+//! Per-input parameters, for puzzles whose examples use different constants to the real input,
+//! such as day14's grid size. They live in an optional `params.toml` next to `input.txt`, so the
+//! puzzle input itself can stay exactly as published.
+//!
+//! Only a small subset of TOML is understood: `key = value` lines, where the value is an
+//! integer, float, boolean or double-quoted string, plus blank lines and `#` comments.
+
+use std::{fmt, path::Path, str::FromStr};
+
+use crate::parse::{self, ParseError};
+
+pub const FILE_NAME: &str = "params.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    /// Values in file order, with strings already unquoted.
+    values: Vec<(String, String)>,
+}
+
+/// A parameter whose value doesn't have the type the solver asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamError {
+    pub name: String,
+    pub value: String,
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parameter {} = {:?} isn't a valid {}",
+            self.name, self.value, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+impl Params {
+    pub fn parse(text: &str) -> Result<Params, ParseError> {
+        let mut params = Params::default();
+        for line in parse::lines(text) {
+            let content = line.text.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once("=", "key = value")?;
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(line.error_at(key, "a key of letters, digits and underscores"));
+            }
+            let value = value.trim();
+            let value = match value.strip_prefix('"') {
+                Some(quoted) => unquote(quoted).ok_or_else(|| {
+                    line.error_at(value, "a double-quoted string with nothing after it")
+                })?,
+                None if value.is_empty() => return Err(line.end_error("a value")),
+                None => {
+                    // Allow a trailing comment after a bare value.
+                    let bare = value.split('#').next().unwrap_or_default().trim_end();
+                    if bare.is_empty() {
+                        return Err(line.error_at(value, "a value"));
+                    }
+                    if bare.contains(char::is_whitespace) {
+                        return Err(line.error_at(bare, "a number, boolean or quoted string"));
+                    }
+                    bare.to_owned()
+                }
+            };
+            if params.raw(key).is_some() {
+                return Err(line.error_at(key, "each key only once"));
+            }
+            params.values.push((key.to_owned(), value));
+        }
+        Ok(params)
+    }
+
+    /// Loads `params.toml` from an input directory. A missing file means no parameters.
+    pub fn load(dir: &Path) -> Result<Params, String> {
+        let path = dir.join(FILE_NAME);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Params::parse(&text).map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Params::default()),
+            Err(e) => Err(format!("couldn't read {}: {e}", path.display())),
+        }
+    }
+
+    /// Sets `name` to `value`, replacing any earlier value, e.g. from the command line.
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.values.retain(|(k, _)| k != name);
+        self.values.push((name.to_owned(), value.to_owned()));
+    }
+
+    pub fn raw(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The value of `name` as a `T`, or `None` if it isn't set.
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<Option<T>, ParamError> {
+        let Some(value) = self.raw(name) else {
+            return Ok(None);
+        };
+        value.parse::<T>().map(Some).map_err(|_| ParamError {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            expected: std::any::type_name::<T>(),
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Unquotes the rest of a basic string after its opening quote. Returns `None` if it isn't
+/// closed, or anything but a comment follows it.
+fn unquote(rest: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = rest.chars();
+    loop {
+        match chars.next()? {
+            '"' => break,
+            '\\' => out.push(match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                c @ ('"' | '\\') => c,
+                _ => return None,
+            }),
+            c => out.push(c),
+        }
+    }
+    let after = chars.as_str().trim();
+    (after.is_empty() || after.starts_with('#')).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(text: &str) -> Vec<(String, String)> {
+        Params::parse(text).unwrap().values
+    }
+
+    #[test]
+    fn parses_values() {
+        let cases: &[(&str, &[(&str, &str)])] = &[
+            ("", &[]),
+            ("width = 11", &[("width", "11")]),
+            ("w=7\nh = -3\n", &[("w", "7"), ("h", "-3")]),
+            ("  ratio =  0.5  ", &[("ratio", "0.5")]),
+            ("verbose = true", &[("verbose", "true")]),
+            ("bare = abc", &[("bare", "abc")]),
+            ("max_steps_2 = 100", &[("max_steps_2", "100")]),
+        ];
+        for (text, expected) in cases {
+            let expected = expected
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(values(text), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn skips_comments_and_blank_lines() {
+        let text = "# Example grid\n\n   # indented\nwidth = 11 # columns\nheight = 7#rows\n\n";
+        assert_eq!(
+            values(text),
+            [
+                ("width".to_owned(), "11".to_owned()),
+                ("height".to_owned(), "7".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn unquotes_strings() {
+        let cases = [
+            (r#"s = "plain""#, "plain"),
+            (r#"s = """#, ""),
+            (
+                r#"s = "with spaces # and a hash""#,
+                "with spaces # and a hash",
+            ),
+            (r#"s = "a = b""#, "a = b"),
+            (r#"s = "\"q\" \\ \n\t""#, "\"q\" \\ \n\t"),
+            (r#"s = "x" # trailing comment"#, "x"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                values(text),
+                [("s".to_owned(), expected.to_owned())],
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let cases = [
+            ("width", 1, 1, "key = value"),
+            ("a = 1\nno equals here", 2, 1, "key = value"),
+            (" = 3", 1, 1, "a key of letters, digits and underscores"),
+            (
+                "grid size = 3",
+                1,
+                1,
+                "a key of letters, digits and underscores",
+            ),
+            (
+                "width-x = 3",
+                1,
+                1,
+                "a key of letters, digits and underscores",
+            ),
+            ("width =", 1, 8, "a value"),
+            ("width = # nothing", 1, 9, "a value"),
+            ("width = 1 2", 1, 9, "a number, boolean or quoted string"),
+            (
+                r#"s = "open"#,
+                1,
+                5,
+                "a double-quoted string with nothing after it",
+            ),
+            (
+                r#"s = "a" b"#,
+                1,
+                5,
+                "a double-quoted string with nothing after it",
+            ),
+            (
+                r#"s = "\x""#,
+                1,
+                5,
+                "a double-quoted string with nothing after it",
+            ),
+            ("w = 1\nw = 2", 2, 1, "each key only once"),
+        ];
+        for (text, line, column, expected) in cases {
+            let e = Params::parse(text).unwrap_err();
+            assert_eq!(
+                (e.line, e.column, e.expected.as_str()),
+                (line, column, expected),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn set_overrides_file_values() {
+        let mut params = Params::parse("width = 11\nheight = 7").unwrap();
+        params.set("width", "101");
+        params.set("seed", "3");
+        assert_eq!(params.get::<usize>("width"), Ok(Some(101)));
+        assert_eq!(params.get::<usize>("seed"), Ok(Some(3)));
+        assert_eq!(params.get::<usize>("depth"), Ok(None));
+        assert!(params.get::<usize>("height").is_ok());
+        assert!(params.get::<bool>("height").is_err());
+    }
+}
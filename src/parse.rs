@@ -0,0 +1,197 @@
+//! Helpers for turning puzzle input into typed values, reporting where and why it went wrong
+//! instead of panicking.
+
+use std::{fmt, str::FromStr};
+
+/// Why some puzzle input couldn't be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// The offending text. Empty if the line or input ended early.
+    pub found: String,
+    /// What was expected instead, e.g. "a tile in the warehouse map".
+    pub expected: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ParseError {
+            line,
+            column,
+            found,
+            expected,
+        } = self;
+        match found.is_empty() {
+            true => write!(f, "line {line} col {column}: expected {expected}"),
+            false => write!(
+                f,
+                "line {line} col {column}: unexpected {found:?}, expected {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A line of puzzle input that knows its position, so errors can point into it.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub text: &'a str,
+    /// 1-based line number.
+    pub number: usize,
+}
+
+impl<'a> Line<'a> {
+    /// An error at the character with index `idx`, e.g. from `text.chars().enumerate()`.
+    pub fn char_error(&self, idx: usize, c: char, expected: &str) -> ParseError {
+        ParseError {
+            line: self.number,
+            column: idx + 1,
+            found: c.to_string(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    /// An error about `part`, which must be a slice of this line's text, e.g. a token from
+    /// `split`. Anything else is reported against the whole line.
+    pub fn error_at(&self, part: &str, expected: &str) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| o + part.len() <= self.text.len());
+        let (column, found) = match offset {
+            Some(o) => (self.text[..o].chars().count() + 1, part),
+            None => (1, self.text),
+        };
+        ParseError {
+            line: self.number,
+            column,
+            found: found.to_owned(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    /// An error about the whole line.
+    pub fn error(&self, expected: &str) -> ParseError {
+        self.error_at(self.text, expected)
+    }
+
+    /// An error for something missing after the end of the line.
+    pub fn end_error(&self, expected: &str) -> ParseError {
+        ParseError {
+            line: self.number,
+            column: self.text.chars().count() + 1,
+            found: String::new(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    /// Parses `part`, a slice of this line's text.
+    pub fn parse<T: FromStr>(&self, part: &'a str, expected: &str) -> Result<T, ParseError> {
+        part.parse::<T>().map_err(|_| self.error_at(part, expected))
+    }
+
+    /// Takes the next token from `tokens`, which must be slices of this line's text.
+    pub fn next(
+        &self,
+        tokens: &mut impl Iterator<Item = &'a str>,
+        expected: &str,
+    ) -> Result<&'a str, ParseError> {
+        tokens.next().ok_or_else(|| self.end_error(expected))
+    }
+
+    /// Takes and parses the next token from `tokens`.
+    pub fn next_parse<T: FromStr>(
+        &self,
+        tokens: &mut impl Iterator<Item = &'a str>,
+        expected: &str,
+    ) -> Result<T, ParseError> {
+        let token = self.next(tokens, expected)?;
+        self.parse(token, expected)
+    }
+
+    /// Checks that `tokens` has nothing left.
+    pub fn finish(&self, tokens: &mut impl Iterator<Item = &'a str>) -> Result<(), ParseError> {
+        match tokens.next() {
+            Some(extra) => Err(self.error_at(extra, "end of line")),
+            None => Ok(()),
+        }
+    }
+
+    pub fn split_once(&self, sep: &str, expected: &str) -> Result<(&'a str, &'a str), ParseError> {
+        self.text
+            .split_once(sep)
+            .ok_or_else(|| self.error(expected))
+    }
+
+    pub fn strip_prefix(&self, prefix: &str, expected: &str) -> Result<&'a str, ParseError> {
+        self.text
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error(expected))
+    }
+}
+
+/// The lines of some puzzle input, numbered from 1.
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    last: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(input: &'a str) -> Lines<'a> {
+        Lines {
+            lines: input.lines().enumerate(),
+            last: 0,
+        }
+    }
+
+    pub fn peek(&self) -> Option<Line<'a>> {
+        self.clone().next()
+    }
+
+    /// Takes the next line, or fails if the input has run out.
+    pub fn next_or(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        let err = self.end_error(expected);
+        self.next().ok_or(err)
+    }
+
+    /// An error for something missing after the last line taken so far, such as a marker that
+    /// should have appeared somewhere in a map.
+    pub fn end_error(&self, expected: &str) -> ParseError {
+        ParseError {
+            line: self.last + 1,
+            column: 1,
+            found: String::new(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    /// Checks that the input has nothing left.
+    pub fn finish(&mut self) -> Result<(), ParseError> {
+        match self.next() {
+            Some(line) => Err(line.error("end of input")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Line<'a>> {
+        let (i, text) = self.lines.next()?;
+        self.last = i + 1;
+        Some(Line {
+            text,
+            number: i + 1,
+        })
+    }
+}
+
+pub fn lines(input: &str) -> Lines<'_> {
+    Lines::new(input)
+}
@@ -0,0 +1,202 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::{
+    direction::{Direction, DIRECTIONS},
+    facing::{Facing, FACINGS},
+};
+
+/// A position on a grid, or the offset between two, with `y` increasing downwards.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const ZERO: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Point {
+        Point { x, y }
+    }
+
+    /// Wraps each coordinate into `0..size`, e.g. for day14's robots teleporting across the
+    /// edges of their room.
+    pub fn rem_euclid(self, size: Point) -> Point {
+        Point::new(self.x.rem_euclid(size.x), self.y.rem_euclid(size.y))
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The four points sharing an edge with this one, in `DIRECTIONS` order.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        DIRECTIONS.into_iter().map(move |d| self + d)
+    }
+
+    /// The eight points sharing an edge or corner with this one, in `FACINGS` order.
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        FACINGS.iter().map(move |f| self + *f)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Point {
+        Point { x, y }
+    }
+}
+
+impl From<Point> for (isize, isize) {
+    fn from(p: Point) -> (isize, isize) {
+        (p.x, p.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: isize) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, d: Direction) -> Point {
+        self + d.offset()
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, d: Direction) {
+        *self = *self + d;
+    }
+}
+
+impl Add<Facing> for Point {
+    type Output = Point;
+
+    fn add(self, f: Facing) -> Point {
+        self + f.offset()
+    }
+}
+
+impl AddAssign<Facing> for Point {
+    fn add_assign(&mut self, f: Facing) {
+        *self = *self + f;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let (a, b) = (Point::new(3, -2), Point::new(-1, 5));
+        assert_eq!(a + b, Point::new(2, 3));
+        assert_eq!(a - b, Point::new(4, -7));
+        assert_eq!(a * 3, Point::new(9, -6));
+        assert_eq!(-a, Point::new(-3, 2));
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+        c -= b;
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn steps() {
+        let p = Point::new(2, 2);
+        assert_eq!(p + Direction::Up, Point::new(2, 1));
+        assert_eq!(p + Direction::Left, Point::new(1, 2));
+        assert_eq!(p + Facing::NE, Point::new(3, 1));
+        assert_eq!(p + Facing::SW, Point::new(1, 3));
+        let mut q = p;
+        q += Direction::Down;
+        q += Facing::W;
+        assert_eq!(q, Point::new(1, 3));
+    }
+
+    #[test]
+    fn wraps_and_measures() {
+        let size = Point::new(11, 7);
+        assert_eq!(Point::new(-1, 7).rem_euclid(size), Point::new(10, 0));
+        assert_eq!(Point::new(23, -15).rem_euclid(size), Point::new(1, 6));
+        assert_eq!(Point::new(1, -2).manhattan(Point::new(-3, 4)), 10);
+        assert_eq!(Point::ZERO.manhattan(Point::ZERO), 0);
+    }
+
+    #[test]
+    fn neighbours() {
+        let n4 = Point::ZERO.neighbours4().map(<(isize, isize)>::from);
+        assert_eq!(n4.collect::<Vec<_>>(), [(0, -1), (1, 0), (0, 1), (-1, 0)]);
+        let n8 = Point::ZERO.neighbours8().map(<(isize, isize)>::from);
+        assert_eq!(
+            n8.collect::<Vec<_>>(),
+            [
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1)
+            ]
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Point::new(-4, 12).to_string(), "-4,12");
+        assert_eq!(Point::from((5, -6)), Point::new(5, -6));
+        assert_eq!(<(isize, isize)>::from(Point::new(5, -6)), (5, -6));
+    }
+}
@@ -0,0 +1,315 @@
+//! Connected regions of a `Map`, such as day12's garden plots, and their shapes.
+
+use std::collections::HashSet;
+
+use crate::{
+    direction::{Direction, DIRECTIONS},
+    map::Map,
+    point::Point,
+    search,
+};
+
+/// A map's tiles split into regions, where neighbouring tiles are in the same region if they
+/// share an edge and the predicate passed to `Regions::new` says they're alike.
+#[derive(Debug, Clone)]
+pub struct Regions {
+    /// The region of each tile.
+    labels: Map<usize>,
+    /// Each region's tiles, in reading order.
+    tiles: Vec<Vec<Point>>,
+}
+
+impl Regions {
+    pub fn new<T>(map: &Map<T>, mut alike: impl FnMut(&T, &T) -> bool) -> Regions {
+        let mut labels = map.map(|_| None::<usize>);
+        let mut tiles = Vec::<Vec<Point>>::new();
+        let mut pending = Vec::<Point>::new();
+        for start in map.positions() {
+            if labels.at(start).flatten().is_some() {
+                continue;
+            }
+            let id = tiles.len();
+            let mut region = vec![];
+            *labels.at_mut(start).unwrap() = Some(id);
+            pending.push(start);
+            while let Some(p) = pending.pop() {
+                region.push(p);
+                let tile = map.get(p).unwrap();
+                for n in p.neighbours4() {
+                    let Some(next) = map.get(n) else {
+                        continue;
+                    };
+                    let label = labels.at_mut(n).unwrap();
+                    if label.is_none() && alike(tile, next) {
+                        *label = Some(id);
+                        pending.push(n);
+                    }
+                }
+            }
+            region.sort_by_key(|p| (p.y, p.x));
+            tiles.push(region);
+        }
+        Regions {
+            labels: labels.map(|l| l.unwrap()),
+            tiles,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// The region that `p` is in, if it's on the map.
+    pub fn region_at(&self, p: Point) -> Option<Region<'_>> {
+        self.labels.at(p).map(|id| self.get(id))
+    }
+
+    /// Region `id`, numbered from 0 in the reading order of their first tiles.
+    pub fn get(&self, id: usize) -> Region<'_> {
+        Region {
+            id,
+            tiles: &self.tiles[id],
+            labels: &self.labels,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Region<'_>> {
+        (0..self.len()).map(|id| self.get(id))
+    }
+}
+
+/// One region of a map.
+#[derive(Debug, Clone, Copy)]
+pub struct Region<'a> {
+    pub id: usize,
+    /// The region's tiles, in reading order.
+    pub tiles: &'a [Point],
+    labels: &'a Map<usize>,
+}
+
+impl Region<'_> {
+    pub fn contains(&self, p: Point) -> bool {
+        self.labels.at(p) == Some(self.id)
+    }
+
+    pub fn area(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// The number of tile edges between the region and anything else, including the edge of
+    /// the map.
+    pub fn perimeter(&self) -> usize {
+        self.tiles
+            .iter()
+            .flat_map(|p| p.neighbours4())
+            .filter(|n| !self.contains(*n))
+            .count()
+    }
+
+    /// The number of corners around the region's outside and any holes. Every straight side
+    /// ends in exactly one corner, so this is also the number of sides.
+    pub fn corners(&self) -> usize {
+        let mut corners = 0;
+        for p in self.tiles.iter() {
+            for d in DIRECTIONS {
+                // Check the corner between `d` and the direction clockwise from it.
+                let cw = Direction::turn_right(d);
+                let (a, b) = (self.contains(*p + d), self.contains(*p + cw));
+                let diagonal = self.contains(*p + d + cw);
+                // Either the region turns outwards here, or it turns inwards around a tile
+                // that isn't in it.
+                if (!a && !b) || (a && b && !diagonal) {
+                    corners += 1;
+                }
+            }
+        }
+        corners
+    }
+
+    pub fn sides(&self) -> usize {
+        self.corners()
+    }
+
+    /// The top left and bottom right corners of the smallest rectangle around the region,
+    /// inclusive.
+    pub fn bounding_box(&self) -> (Point, Point) {
+        let xs = self.tiles.iter().map(|p| p.x);
+        let ys = self.tiles.iter().map(|p| p.y);
+        (
+            Point::new(xs.clone().min().unwrap(), ys.clone().min().unwrap()),
+            Point::new(xs.max().unwrap(), ys.max().unwrap()),
+        )
+    }
+
+    /// The areas enclosed by the region, each as its tiles in reading order. A hole may contain
+    /// other regions. Holes touch their surroundings only through the region, even diagonally,
+    /// since a diagonal gap between two of its tiles leaves a way out.
+    pub fn holes(&self) -> Vec<Vec<Point>> {
+        let (min, max) = self.bounding_box();
+        let outside = (min - Point::new(1, 1), max + Point::new(1, 1));
+        let in_frame = |p: &Point| {
+            (outside.0.x..=outside.1.x).contains(&p.x) && (outside.0.y..=outside.1.y).contains(&p.y)
+        };
+        let open = |p: &Point| in_frame(p) && !self.contains(*p);
+        let flood = |start: Point| {
+            search::bfs([start], |p| {
+                p.neighbours8().filter(open).collect::<Vec<_>>()
+            })
+        };
+
+        // Everything reachable from the frame around the bounding box is outside the region.
+        let mut seen = flood(outside.0)
+            .distances()
+            .map(|(p, _)| *p)
+            .collect::<HashSet<_>>();
+        let mut holes = Vec::<Vec<Point>>::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let p = Point::new(x, y);
+                if self.contains(p) || seen.contains(&p) {
+                    continue;
+                }
+                let mut hole = flood(p).distances().map(|(p, _)| *p).collect::<Vec<_>>();
+                seen.extend(hole.iter().copied());
+                hole.sort_by_key(|p| (p.y, p.x));
+                holes.push(hole);
+            }
+        }
+        holes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn label(text: &str) -> (Map<char>, Regions) {
+        let (map, _) = Map::parse(&mut parse::lines(text), &[], Ok).unwrap();
+        let regions = Regions::new(&map, |a, b| a == b);
+        (map, regions)
+    }
+
+    fn points(ps: &[(isize, isize)]) -> Vec<Point> {
+        ps.iter().map(|p| Point::from(*p)).collect()
+    }
+
+    #[test]
+    fn measures_regions() {
+        let (map, regions) = label("AAAA\nBBCD\nBBCC\nEEEC");
+        let shapes = regions
+            .iter()
+            .map(|r| {
+                let c = map.at(r.tiles[0]).unwrap();
+                (c, r.area(), r.perimeter(), r.sides())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            shapes,
+            [
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_unconnected_tiles_that_are_alike() {
+        let (_, regions) = label("ABA\nBAB");
+        assert_eq!(regions.len(), 6);
+        // Diagonal neighbours aren't connected.
+        let a = regions.region_at(Point::new(0, 0)).unwrap();
+        assert_eq!(a.tiles, points(&[(0, 0)]));
+        assert!(!a.contains(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn looks_up_regions_by_position() {
+        let (_, regions) = label("AAB\nABB\nCCB");
+        let b = regions.region_at(Point::new(2, 2)).unwrap();
+        assert_eq!(b.id, 1);
+        assert_eq!(b.tiles, points(&[(2, 0), (1, 1), (2, 1), (2, 2)]));
+        assert!(b.contains(Point::new(1, 1)));
+        assert!(!b.contains(Point::new(0, 0)));
+        assert!(!b.contains(Point::new(3, 0)));
+        assert_eq!(b.bounding_box(), (Point::new(1, 0), Point::new(2, 2)));
+        assert_eq!(regions.get(2).tiles, points(&[(0, 2), (1, 2)]));
+        assert!(regions.region_at(Point::new(-1, 0)).is_none());
+        assert!(regions.region_at(Point::new(0, 3)).is_none());
+    }
+
+    #[test]
+    fn finds_holes() {
+        let (_, regions) = label("AAA\nA.A\nAAA");
+        let a = regions.get(0);
+        assert_eq!(a.holes(), [points(&[(1, 1)])]);
+        // A hole's edges count as sides too.
+        assert_eq!(a.sides(), 8);
+        assert!(regions.get(1).holes().is_empty());
+    }
+
+    #[test]
+    fn nested_holes_contain_other_regions() {
+        let (_, regions) = label(
+            "AAAAAAA\n\
+             ABBBBBA\n\
+             ABCCCBA\n\
+             ABCDCBA\n\
+             ABCCCBA\n\
+             ABBBBBA\n\
+             AAAAAAA",
+        );
+        let hole_sizes = regions
+            .iter()
+            .map(|r| r.holes().iter().map(Vec::len).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(hole_sizes, [vec![25], vec![9], vec![1], vec![]]);
+        assert_eq!(
+            regions.get(1).holes()[0],
+            points(&[
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (2, 3),
+                (3, 3),
+                (4, 3),
+                (2, 4),
+                (3, 4),
+                (4, 4)
+            ])
+        );
+    }
+
+    #[test]
+    fn separate_holes() {
+        let (_, regions) = label("AAAAA\nA.A.A\nAAAAA");
+        assert_eq!(
+            regions.get(0).holes(),
+            [points(&[(1, 1)]), points(&[(3, 1)])]
+        );
+    }
+
+    #[test]
+    fn diagonal_gaps_are_not_holes() {
+        // The middle tile is cut off from the outside along edges, but not across the corner
+        // between the two `A`s at the bottom right.
+        let (_, regions) = label("AAA.\nA.A.\nAA..");
+        assert!(regions.get(0).holes().is_empty());
+
+        let (_, regions) = label("AAA.\nA.A.\nAAA.");
+        assert_eq!(regions.get(0).holes(), [points(&[(1, 1)])]);
+    }
+
+    #[test]
+    fn the_map_edge_is_not_a_wall() {
+        let (_, regions) = label("A.A\nAAA");
+        assert!(regions.get(0).holes().is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+use std::time::Duration;
+
+use crate::{
+    answer::Answer,
+    day_output::Timings,
+    error::{Crash, Failure, Mismatch},
+    json,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    NoExpected,
+    NoResult,
+    /// The solver panicked before answering this part.
+    Crash,
+    /// The run hit its time limit before answering this part.
+    Timeout,
+}
+
+impl Status {
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::NoExpected => "NO-EXPECTED",
+            Status::NoResult => "NO-RESULT",
+            Status::Crash => "CRASH",
+            Status::Timeout => "TIMEOUT",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    pub status: Status,
+    pub actual: Option<String>,
+    pub expected: Option<String>,
+}
+
+impl PartReport {
+    pub fn new(actual: Option<&Answer>, expected: &Option<String>) -> PartReport {
+        let status = match (actual, expected) {
+            (None, _) => Status::NoResult,
+            (Some(_), None) => Status::NoExpected,
+            (Some(a), Some(e)) => match a.matches(e) {
+                true => Status::Pass,
+                false => Status::Fail,
+            },
+        };
+        PartReport {
+            status,
+            actual: actual.map(|a| a.to_string()),
+            expected: expected.as_ref().map(|e| e.trim().to_owned()),
+        }
+    }
+
+    /// A part fails if it produced the wrong answer, or no answer when one was expected. Crashes
+    /// and timeouts are reported once for the whole run instead.
+    pub fn is_failure(&self) -> bool {
+        match self.status {
+            Status::Fail => true,
+            Status::NoResult => self.expected.is_some(),
+            Status::Pass | Status::NoExpected | Status::Crash | Status::Timeout => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub day: String,
+    pub input: String,
+    pub duration: Duration,
+    pub timings: Timings,
+    pub parts: [PartReport; 2],
+    pub diagnostics: Vec<(String, String)>,
+    /// Set if the solver returned an error rather than finishing normally.
+    pub error: Option<String>,
+    /// Set if the solver panicked, or its input or parameters couldn't be read.
+    pub crash: Option<Crash>,
+    /// The time limit, if the run hit it.
+    pub timeout: Option<Duration>,
+}
+
+impl RunReport {
+    pub fn failures(&self) -> Vec<Failure> {
+        let mut failures = self
+            .parts
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_failure())
+            .map(|(i, p)| {
+                Failure::Mismatch(Mismatch {
+                    day: self.day.clone(),
+                    input: self.input.clone(),
+                    part: i + 1,
+                    expected: p.expected.clone().unwrap_or_default(),
+                    actual: p.actual.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        if let Some(message) = &self.error {
+            failures.push(Failure::Solver {
+                day: self.day.clone(),
+                input: self.input.clone(),
+                message: message.clone(),
+            });
+        }
+        if let Some(crash) = &self.crash {
+            failures.push(Failure::Crash {
+                day: self.day.clone(),
+                input: self.input.clone(),
+                crash: crash.clone(),
+            });
+        }
+        if let Some(limit) = self.timeout {
+            failures.push(Failure::Timeout {
+                day: self.day.clone(),
+                input: self.input.clone(),
+                limit,
+            });
+        }
+        failures
+    }
+
+    /// Encodes this run as a single-line JSON object. The schema is versioned by the `schema`
+    /// field and only ever gains fields within a version:
+    ///
+    /// ```text
+    /// {
+    ///   "schema": 1,
+    ///   "day": "day01",
+    ///   "input": "example",
+    ///   "duration_ns": 10097,
+    ///   "parse_ns": 4120,
+    ///   "part1_ns": 3302,
+    ///   "part2_ns": 2675,
+    ///   "part1": {"status": "PASS", "actual": "11", "expected": "11"},
+    ///   "part2": {"status": "NO-EXPECTED", "actual": "31", "expected": null},
+    ///   "diagnostics": {"swaps": "a<->b c<->d"},
+    ///   "error": null,
+    ///   "crash": {"message": "attempt to subtract with overflow", "location": "src/days/day05.rs:40:9"},
+    ///   "timeout_ns": null
+    /// }
+    /// ```
+    ///
+    /// The `*_ns` phase timings are `null` if the solver didn't mark that phase; see `Timings`.
+    /// `status` is one of the labels shown in the summary table. `actual` and `expected` are
+    /// `null` when there is no answer or no expectation file respectively. `diagnostics` holds any
+    /// named values the solver attached, and `error` is the solver's error message if it failed.
+    /// `crash` is `null` unless the solver panicked, in which case `location` may still be
+    /// `null`, e.g. if the input file couldn't be read. `timeout_ns` is the time limit if the run
+    /// hit it, and `null` otherwise.
+    pub fn to_json(&self) -> String {
+        let nanos = |d: Option<Duration>| match d {
+            Some(d) => d.as_nanos().to_string(),
+            None => "null".to_owned(),
+        };
+        let part = |p: &PartReport| {
+            json::object(&[
+                ("status", json::string(p.status.label())),
+                ("actual", json::opt_string(&p.actual)),
+                ("expected", json::opt_string(&p.expected)),
+            ])
+        };
+        json::object(&[
+            ("schema", "1".to_owned()),
+            ("day", json::string(&self.day)),
+            ("input", json::string(&self.input)),
+            ("duration_ns", self.duration.as_nanos().to_string()),
+            ("parse_ns", nanos(self.timings.parse)),
+            ("part1_ns", nanos(self.timings.part1)),
+            ("part2_ns", nanos(self.timings.part2)),
+            ("part1", part(&self.parts[0])),
+            ("part2", part(&self.parts[1])),
+            (
+                "diagnostics",
+                json::object(
+                    &self
+                        .diagnostics
+                        .iter()
+                        .map(|(k, v)| (k.as_str(), json::string(v)))
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+            ("error", json::opt_string(&self.error)),
+            (
+                "crash",
+                match &self.crash {
+                    Some(c) => json::object(&[
+                        ("message", json::string(&c.message)),
+                        ("location", json::opt_string(&c.location)),
+                    ]),
+                    None => "null".to_owned(),
+                },
+            ),
+            ("timeout_ns", nanos(self.timeout)),
+        ])
+    }
+}
+
+pub fn print_run(report: &RunReport) {
+    let name = &report.input;
+    let phases = [
+        ("parse", report.timings.parse),
+        ("part 1", report.timings.part1),
+        ("part 2", report.timings.part2),
+    ]
+    .iter()
+    .filter_map(|(phase, d)| Some(format!("{phase} {:#?}", (*d)?)))
+    .collect::<Vec<_>>();
+    match phases.is_empty() {
+        true => println!("    [{name}] Took {:#?}", report.duration),
+        false => println!(
+            "    [{name}] Took {:#?} ({})",
+            report.duration,
+            phases.join(", ")
+        ),
+    }
+    for (i, p) in report.parts.iter().enumerate() {
+        let part = i + 1;
+        match (&p.actual, &p.expected) {
+            (None, None) => {
+                println!("    [{name}] Part {part}: No result nor expected result")
+            }
+            (None, Some(e)) => {
+                println!("    [{name}] Part {part}: No result, but expected {e}")
+            }
+            (Some(a), None) => {
+                println!("    [{name}] Part {part}: Result was {a}")
+            }
+            (Some(a), Some(e)) => match p.status {
+                Status::Pass => {
+                    println!("    [{name}] Part {part}: PASS Result was {a} as expected")
+                }
+                _ => println!("    [{name}] Part {part}: FAIL Result was {a} but expected {e}"),
+            },
+        }
+    }
+    for (k, v) in report.diagnostics.iter() {
+        match v.trim_end().contains('\n') {
+            true => {
+                println!("    [{name}] {k}:");
+                for line in v.trim_end().lines() {
+                    println!("        {line}");
+                }
+            }
+            false => println!("    [{name}] {k}: {v}"),
+        }
+    }
+    if let Some(e) = &report.error {
+        println!("    [{name}] ERROR {e}");
+    }
+    if let Some(c) = &report.crash {
+        println!("    [{name}] CRASH {c}");
+    }
+    if let Some(limit) = report.timeout {
+        println!("    [{name}] TIMEOUT after {limit:#?}");
+    }
+}
+
+pub fn print_summary(reports: &[RunReport]) {
+    let rows = reports
+        .iter()
+        .map(|r| {
+            vec![
+                r.day.clone(),
+                r.input.clone(),
+                r.parts[0].status.label().to_owned(),
+                r.parts[1].status.label().to_owned(),
+                format!("{:#?}", r.duration),
+                opt_duration(r.timings.parse),
+                opt_duration(r.timings.part1),
+                opt_duration(r.timings.part2),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    println!();
+    print_table(
+        &[
+            "day",
+            "input",
+            "part 1",
+            "part 2",
+            "time",
+            "parse",
+            "part 1 time",
+            "part 2 time",
+        ],
+        &rows,
+    );
+
+    let failures = reports.iter().map(|r| r.failures().len()).sum::<usize>();
+    let passes = reports
+        .iter()
+        .flat_map(|r| r.parts.iter())
+        .filter(|p| p.status == Status::Pass)
+        .count();
+    println!();
+    println!("{passes} passed, {failures} failed");
+}
+
+fn opt_duration(d: Option<Duration>) -> String {
+    match d {
+        Some(d) => format!("{d:#?}"),
+        None => "-".to_owned(),
+    }
+}
+
+/// Prints left-aligned columns sized to fit their widest cell.
+pub fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in rows.iter() {
+        for (w, cell) in widths.iter_mut().zip(row.iter()) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |cells: &mut dyn Iterator<Item = &str>| {
+        let line = cells
+            .zip(widths.iter())
+            .map(|(c, w)| format!("{c:<w$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    };
+
+    print_row(&mut header.iter().copied());
+    let rules = widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>();
+    print_row(&mut rules.iter().map(|s| s.as_str()));
+    for row in rows.iter() {
+        print_row(&mut row.iter().map(|s| s.as_str()));
+    }
+}
@@ -0,0 +1,127 @@
+//! Generates the boilerplate for a new day's solver, and new input cases for existing days.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{days::DAYS, error::Error};
+
+const SOLVER_TEMPLATE: &str = "use crate::{
+    day_output::{DayError, DayOutput},
+    parse,
+};
+
+pub fn main(input: &str, output: &mut DayOutput) -> Result<(), DayError> {
+    let lines = parse::lines(input).map(|line| line.text).collect::<Vec<_>>();
+    output.parsed();
+
+    output.diagnostic(\"lines\", lines.len());
+    Ok(())
+}
+";
+
+/// Normalises a day name such as `day7`, `day07` or `7` to `day07`.
+pub fn day_name(name: &str) -> Result<String, Error> {
+    name.strip_prefix("day")
+        .unwrap_or(name)
+        .parse::<usize>()
+        .map(|n| format!("day{n:02}"))
+        .map_err(|_| Error::Usage(format!("{name} isn't a day name such as day07")))
+}
+
+/// Adds a solver for `day` from a template: its module under `src_root/days`, its entries in
+/// `src_root/days.rs`, and an empty example input under `inputs_root`. Returns the paths it
+/// created or edited.
+pub fn new_day(src_root: &Path, inputs_root: &Path, day: &str) -> Result<Vec<PathBuf>, Error> {
+    let day = day_name(day)?;
+    let solver_path = src_root.join("days").join(format!("{day}.rs"));
+    if DAYS.iter().any(|(name, _)| *name == day) || solver_path.exists() {
+        return Err(Error::Usage(format!("{day} already exists")));
+    }
+
+    let registry_path = src_root.join("days.rs");
+    let registry = read(&registry_path)?;
+    let registry = insert_sorted(&registry, "mod day", &format!("mod {day};"))
+        .and_then(|r| insert_sorted(&r, "    (\"day", &format!("    (\"{day}\", {day}::main),")))
+        .ok_or_else(|| {
+            Error::Io(format!(
+                "Couldn't find the day list in {}",
+                registry_path.display()
+            ))
+        })?;
+
+    let example_dir = inputs_root.join(&day).join("example");
+    write(&solver_path, SOLVER_TEMPLATE)?;
+    write(&registry_path, &registry)?;
+    create_dir(&example_dir)?;
+    let example_path = example_dir.join("input.txt");
+    if !example_path.exists() {
+        write(&example_path, "")?;
+    }
+    Ok(vec![solver_path, registry_path, example_path])
+}
+
+/// Creates input case `name` for `day` under `inputs_root`, with the given puzzle input and
+/// expected answers. Returns the case's directory.
+pub fn add_case(
+    inputs_root: &Path,
+    day: &str,
+    name: &str,
+    input: &str,
+    part1: Option<&str>,
+    part2: Option<&str>,
+) -> Result<PathBuf, Error> {
+    let day = day_name(day)?;
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(Error::Usage(format!("{name:?} isn't a valid input name")));
+    }
+    let dir = inputs_root.join(&day).join(name);
+    if dir.exists() {
+        return Err(Error::Usage(format!("{} already exists", dir.display())));
+    }
+
+    create_dir(&dir)?;
+    write(&dir.join("input.txt"), input)?;
+    for (file_name, expected) in [("part1.txt", part1), ("part2.txt", part2)] {
+        if let Some(expected) = expected {
+            write(&dir.join(file_name), expected.trim())?;
+        }
+    }
+    Ok(dir)
+}
+
+/// Inserts `line` among the run of lines starting with `prefix`, keeping the run sorted. Returns
+/// `None` if there is no such run.
+fn insert_sorted(text: &str, prefix: &str, line: &str) -> Option<String> {
+    let mut lines = text.lines().collect::<Vec<_>>();
+    let first = lines.iter().position(|l| l.starts_with(prefix))?;
+    let run_len = lines[first..]
+        .iter()
+        .take_while(|l| l.starts_with(prefix))
+        .count();
+    let at = first
+        + lines[first..first + run_len]
+            .iter()
+            .take_while(|l| **l < line)
+            .count();
+    lines.insert(at, line);
+    let mut text = lines.join("\n");
+    text.push('\n');
+    Some(text)
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path)
+        .map_err(|e| Error::Io(format!("Couldn't read {}: {e}", path.display())))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    fs::write(path, contents)
+        .map_err(|e| Error::Io(format!("Couldn't write {}: {e}", path.display())))
+}
+
+fn create_dir(path: &Path) -> Result<(), Error> {
+    fs::create_dir_all(path)
+        .map_err(|e| Error::Io(format!("Couldn't create {}: {e}", path.display())))
+}
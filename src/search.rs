@@ -0,0 +1,449 @@
+//! Shortest paths over implicit graphs, where a state's neighbours come from a successor function
+//! rather than a stored graph, e.g. a position and facing in a maze.
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use crate::fxhash::FxBuildHasher;
+
+/// Every state a search reached, with its distance from the nearest start and its predecessors
+/// on all of the shortest paths there. These form a DAG unless zero-cost steps loop.
+#[derive(Debug, Clone)]
+pub struct Paths<S> {
+    reached: HashMap<S, Reached<S>, FxBuildHasher>,
+}
+
+#[derive(Debug, Clone)]
+struct Reached<S> {
+    distance: usize,
+    /// Empty for the starts.
+    predecessors: Vec<S>,
+}
+
+impl<S: Clone + Eq + Hash> Paths<S> {
+    fn new() -> Paths<S> {
+        Paths {
+            reached: HashMap::default(),
+        }
+    }
+
+    /// Records reaching `state` from `from` at `distance`. Returns whether that's a new shortest
+    /// distance, so `state` needs (re)visiting. Ties only add a predecessor.
+    fn reach(&mut self, state: S, from: Option<&S>, distance: usize) -> bool {
+        match self.reached.entry(state) {
+            Entry::Vacant(e) => {
+                e.insert(Reached {
+                    distance,
+                    predecessors: from.into_iter().cloned().collect(),
+                });
+                true
+            }
+            Entry::Occupied(mut e) => {
+                let r = e.get_mut();
+                if distance < r.distance {
+                    r.distance = distance;
+                    r.predecessors = from.into_iter().cloned().collect();
+                    true
+                } else {
+                    if distance == r.distance {
+                        r.predecessors.extend(from.cloned());
+                    }
+                    false
+                }
+            }
+        }
+    }
+
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.reached.get(state).map(|r| r.distance)
+    }
+
+    /// Every state reached, with its distance, in no particular order.
+    pub fn distances(&self) -> impl Iterator<Item = (&S, usize)> {
+        self.reached.iter().map(|(s, r)| (s, r.distance))
+    }
+
+    /// The states that `state` can be reached from on a shortest path.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.reached
+            .get(state)
+            .map_or(&[], |r| r.predecessors.as_slice())
+    }
+
+    /// One shortest path from a start to `goal`, including both ends.
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        self.reached.get(goal)?;
+        let mut path = vec![goal.clone()];
+        while let Some(prev) = self.predecessors(path.last().unwrap()).first() {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every shortest path from a start to `goal`, each including both ends. There can be
+    /// exponentially many, so this is for small graphs such as a keypad.
+    pub fn paths_to(&self, goal: &S) -> Vec<Vec<S>> {
+        if !self.reached.contains_key(goal) {
+            return vec![];
+        }
+        let predecessors = self.predecessors(goal);
+        if predecessors.is_empty() {
+            return vec![vec![goal.clone()]];
+        }
+        let mut paths = Vec::<Vec<S>>::new();
+        for prev in predecessors {
+            for mut path in self.paths_to(prev) {
+                path.push(goal.clone());
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Every state on any shortest path to any of `goals`, including the starts and goals.
+    pub fn on_shortest_paths(
+        &self,
+        goals: impl IntoIterator<Item = S>,
+    ) -> HashSet<S, FxBuildHasher> {
+        let mut on_path = HashSet::<S, FxBuildHasher>::default();
+        let mut pending = goals
+            .into_iter()
+            .filter(|g| self.reached.contains_key(g))
+            .collect::<Vec<_>>();
+        while let Some(state) = pending.pop() {
+            if on_path.insert(state.clone()) {
+                pending.extend(self.predecessors(&state).iter().cloned());
+            }
+        }
+        on_path
+    }
+
+    /// How many distinct shortest paths lead to each reached state from the starts.
+    ///
+    /// States are counted in topological order over the predecessors rather than by distance,
+    /// since after zero-cost steps a predecessor can be as far from the starts as its successor.
+    /// Panics if zero-cost steps form a cycle, as there are then infinitely many shortest paths.
+    pub fn path_counts(&self) -> HashMap<S, usize, FxBuildHasher> {
+        let mut successors = HashMap::<&S, Vec<&S>, FxBuildHasher>::default();
+        let mut waiting_on = HashMap::<&S, usize, FxBuildHasher>::default();
+        let mut ready = Vec::<&S>::new();
+        for (state, r) in self.reached.iter() {
+            for p in r.predecessors.iter() {
+                successors.entry(p).or_default().push(state);
+            }
+            match r.predecessors.len() {
+                0 => ready.push(state),
+                n => {
+                    waiting_on.insert(state, n);
+                }
+            }
+        }
+
+        let mut counts = HashMap::<S, usize, FxBuildHasher>::with_capacity_and_hasher(
+            self.reached.len(),
+            Default::default(),
+        );
+        while let Some(state) = ready.pop() {
+            let predecessors = self.predecessors(state);
+            let count = match predecessors.is_empty() {
+                true => 1,
+                false => predecessors.iter().map(|p| counts[p]).sum(),
+            };
+            counts.insert(state.clone(), count);
+            for next in successors.get(state).into_iter().flatten() {
+                let waiting = waiting_on.get_mut(next).unwrap();
+                *waiting -= 1;
+                if *waiting == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+        assert_eq!(
+            counts.len(),
+            self.reached.len(),
+            "zero-cost steps form a cycle, so some states have infinitely many shortest paths"
+        );
+        counts
+    }
+}
+
+/// Breadth-first search from `starts`, where every step costs 1.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+) -> Paths<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::<S>::new();
+    for start in starts {
+        if paths.reach(start.clone(), None, 0) {
+            queue.push_back(start);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        let distance = paths.distance(&state).unwrap() + 1;
+        for next in successors(&state) {
+            if paths.reach(next.clone(), Some(&state), distance) {
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+/// Dijkstra's algorithm from `starts`, where `successors` gives each next state with the cost of
+/// stepping to it.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+) -> Paths<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut paths = Paths::new();
+    // The heap holds indices into `states`, so states themselves needn't be `Ord`.
+    let mut states = Vec::<S>::new();
+    let mut heap = BinaryHeap::<Reverse<(usize, usize)>>::new();
+    for start in starts {
+        if paths.reach(start.clone(), None, 0) {
+            heap.push(Reverse((0, states.len())));
+            states.push(start);
+        }
+    }
+    while let Some(Reverse((distance, idx))) = heap.pop() {
+        let state = states[idx].clone();
+        if paths.distance(&state) != Some(distance) {
+            // Superseded by a shorter path found after this was queued.
+            continue;
+        }
+        for (next, cost) in successors(&state) {
+            if paths.reach(next.clone(), Some(&state), distance + cost) {
+                heap.push(Reverse((distance + cost, states.len())));
+                states.push(next);
+            }
+        }
+    }
+    paths
+}
+
+/// A* search from `start` to the nearest state accepted by `is_goal`. `heuristic` must never
+/// overestimate the remaining cost, e.g. the manhattan distance on a grid. Returns the goal's
+/// distance and a shortest path to it, including both ends.
+pub fn astar<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut paths = Paths::new();
+    let mut states = vec![start.clone()];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0usize, 0usize))]);
+    paths.reach(start, None, 0);
+    while let Some(Reverse((_, distance, idx))) = heap.pop() {
+        let state = states[idx].clone();
+        if paths.distance(&state) != Some(distance) {
+            continue;
+        }
+        if is_goal(&state) {
+            return Some((distance, paths.path_to(&state).unwrap()));
+        }
+        for (next, cost) in successors(&state) {
+            let next_distance = distance + cost;
+            if paths.reach(next.clone(), Some(&state), next_distance) {
+                let estimate = next_distance + heuristic(&next);
+                heap.push(Reverse((estimate, next_distance, states.len())));
+                states.push(next);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    /// A diamond with a tail, where every edge costs 1:
+    ///
+    /// ```text
+    ///   1
+    ///  / \
+    /// 0   3 - 4
+    ///  \ /
+    ///   2
+    /// ```
+    ///
+    /// 5 is unreachable.
+    fn diamond(s: &u32) -> Vec<u32> {
+        match s {
+            0 => vec![1, 2],
+            1 | 2 => vec![3],
+            3 => vec![4],
+            5 => vec![0],
+            _ => vec![],
+        }
+    }
+
+    fn sorted<T: Ord>(items: impl IntoIterator<Item = T>) -> Vec<T> {
+        let mut items = items.into_iter().collect::<Vec<_>>();
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn bfs_distances_and_paths() {
+        let paths = bfs([0], diamond);
+        assert_eq!(
+            sorted(paths.distances().map(|(s, d)| (*s, d))),
+            [(0, 0), (1, 1), (2, 1), (3, 2), (4, 3)]
+        );
+        assert_eq!(paths.distance(&5), None);
+        assert_eq!(sorted(paths.predecessors(&3).iter().copied()), [1, 2]);
+        assert_eq!(paths.predecessors(&0), &[] as &[u32]);
+
+        let path = paths.path_to(&4).unwrap();
+        assert!(path == [0, 1, 3, 4] || path == [0, 2, 3, 4], "{path:?}");
+        assert_eq!(paths.path_to(&0), Some(vec![0]));
+        assert_eq!(paths.path_to(&5), None);
+
+        assert_eq!(
+            sorted(paths.paths_to(&4)),
+            [vec![0, 1, 3, 4], vec![0, 2, 3, 4]]
+        );
+        assert_eq!(paths.paths_to(&5), Vec::<Vec<u32>>::new());
+    }
+
+    #[test]
+    fn bfs_from_several_starts() {
+        let paths = bfs([1, 2], diamond);
+        assert_eq!(paths.distance(&0), None);
+        assert_eq!(paths.distance(&4), Some(2));
+        assert_eq!(paths.path_counts()[&4], 2);
+    }
+
+    #[test]
+    fn on_shortest_paths() {
+        let paths = bfs([0], diamond);
+        assert_eq!(sorted(paths.on_shortest_paths([3])), [0, 1, 2, 3]);
+        assert_eq!(sorted(paths.on_shortest_paths([1, 4])), [0, 1, 2, 3, 4]);
+        assert!(paths.on_shortest_paths([5]).is_empty());
+    }
+
+    #[test]
+    fn path_counts() {
+        // Two diamonds in a row, so four shortest paths to the end.
+        let paths = bfs([0], |s: &u32| match s {
+            0 => vec![1, 2],
+            1 | 2 => vec![3],
+            3 => vec![4, 5],
+            4 | 5 => vec![6],
+            _ => vec![],
+        });
+        let counts = paths.path_counts();
+        assert_eq!(
+            sorted(counts.iter().map(|(s, c)| (*s, *c))),
+            [(0, 1), (1, 1), (2, 1), (3, 2), (4, 2), (5, 2), (6, 4)]
+        );
+    }
+
+    #[test]
+    fn path_counts_with_zero_cost_steps() {
+        // 0 reaches 1 and 2 at the same distance, but 2 only by way of 1, and 3 by way of both.
+        let paths = dijkstra([0], |s: &u32| match s {
+            0 => vec![(1, 1), (2, 1)],
+            1 => vec![(2, 0), (3, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        });
+        assert_eq!(paths.distance(&2), Some(1));
+        let counts = paths.path_counts();
+        assert_eq!(counts[&2], 2);
+        assert_eq!(counts[&3], 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "zero-cost steps form a cycle")]
+    fn path_counts_with_zero_cost_cycle() {
+        let paths = dijkstra([0], |s: &u32| match s {
+            0 => vec![(1, 1)],
+            1 => vec![(2, 0)],
+            2 => vec![(1, 0)],
+            _ => vec![],
+        });
+        paths.path_counts();
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_longer_paths() {
+        let paths = dijkstra([0], |s: &u32| match s {
+            0 => vec![(1, 10), (2, 1)],
+            2 => vec![(3, 1)],
+            3 => vec![(1, 1)],
+            _ => vec![],
+        });
+        assert_eq!(paths.distance(&1), Some(3));
+        assert_eq!(paths.path_to(&1), Some(vec![0, 2, 3, 1]));
+        assert_eq!(paths.predecessors(&1), &[3]);
+    }
+
+    /// A line of states 0 to 9 with unit steps both ways, plus a shortcut from 0 to 8 costing 3.
+    fn line(s: &u32) -> Vec<(u32, usize)> {
+        let mut next = vec![(s + 1, 1)];
+        if *s > 0 {
+            next.push((s - 1, 1));
+        }
+        if *s == 0 {
+            next.push((8, 3));
+        }
+        next.retain(|(n, _)| *n < 10);
+        next
+    }
+
+    #[test]
+    fn astar_finds_the_nearest_of_several_goals() {
+        let goals = HashSet::from([5, 9]);
+        let found = astar(0, line, |_| 0, |s| goals.contains(s));
+        assert_eq!(found, Some((4, vec![0, 8, 9])));
+
+        let goals = HashSet::from([2, 9]);
+        let found = astar(0, line, |_| 0, |s| goals.contains(s));
+        assert_eq!(found, Some((2, vec![0, 1, 2])));
+    }
+
+    #[test]
+    fn astar_with_a_heuristic() {
+        // The distance left along the line, capped at the cost from 0 by the shortcut, so it
+        // never overestimates.
+        let found = astar(
+            0,
+            line,
+            |s| 9usize.saturating_sub(*s as usize).min(4),
+            |s| *s == 9,
+        );
+        assert_eq!(found, Some((4, vec![0, 8, 9])));
+    }
+
+    #[test]
+    fn astar_start_is_goal() {
+        assert_eq!(astar(3, line, |_| 0, |s| *s == 3), Some((0, vec![3])));
+    }
+
+    #[test]
+    fn astar_unreachable_goal() {
+        assert_eq!(astar(0, line, |_| 0, |s| *s == 42), None);
+    }
+}
@@ -0,0 +1,265 @@
+//! Re-runs days whenever their inputs or the solver sources change.
+//!
+//! Files are polled for changes rather than watched, which is plenty for a few hundred files.
+//! Each run goes through `cargo run` with `--format json`, so a source change is rebuilt before
+//! it's run, and the watcher itself never needs restarting.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    error::Error,
+    harness::{self, InputFilter, InputRoots},
+    json,
+};
+
+pub struct WatchOptions {
+    pub day: String,
+    /// Which inputs to run, passed on to each run.
+    pub filter: InputFilter,
+    /// `--inputs-dir` roots to pass on to each run. `AOC24_INPUTS` is inherited.
+    pub inputs_dirs: Vec<PathBuf>,
+    pub timeout: Option<Duration>,
+    /// Cargo profile to build and run with, e.g. `release`.
+    pub profile: String,
+    /// How often to check for changes.
+    pub interval: Duration,
+}
+
+/// The parts of one day and input's result that are worth comparing between runs.
+#[derive(Debug, Clone, PartialEq)]
+struct RunSummary {
+    /// Each part's status and answer.
+    parts: [(String, Option<String>); 2],
+    /// How many parts passed and failed, counted as in the normal run summary.
+    passed: usize,
+    failed: usize,
+    /// Any error, crash and timeout, already formatted.
+    problems: Vec<String>,
+}
+
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Runs the selected days, then runs them again each time a file under their input directories
+/// or the crate's sources changes. Only returns if the day selector is invalid.
+pub fn watch(options: &WatchOptions) -> Result<(), Error> {
+    let days = harness::select_days(&options.day)?;
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let sources = [
+        manifest_dir.join("src"),
+        manifest_dir.join("Cargo.toml"),
+        manifest_dir.join("build.rs"),
+    ];
+    let roots = InputRoots::new(&options.inputs_dirs);
+    let mut watched = sources.to_vec();
+    for root in roots.roots() {
+        watched.extend(days.iter().map(|(day, _)| root.join(day)));
+    }
+
+    let mut previous = HashMap::<(String, String), RunSummary>::new();
+    let mut last = snapshot(&watched);
+    println!("Watching {} for changes...", options.day);
+    run(options, &mut previous);
+    loop {
+        thread::sleep(options.interval);
+        let now = snapshot(&watched);
+        if now == last {
+            continue;
+        }
+
+        let changed = now
+            .iter()
+            .filter(|(path, stamp)| last.get(*path) != Some(stamp))
+            .map(|(path, _)| path)
+            .chain(last.keys().filter(|path| !now.contains_key(*path)))
+            .collect::<Vec<_>>();
+        let source_changed = changed
+            .iter()
+            .any(|path| sources.iter().any(|s| path.starts_with(s)));
+        println!();
+        match source_changed {
+            true => println!(
+                "Sources changed, rebuilding and re-running {}...",
+                options.day
+            ),
+            false => println!("Inputs changed, re-running {}...", options.day),
+        }
+
+        // Let editors finish writing before taking the snapshot to compare against next time.
+        thread::sleep(options.interval);
+        last = snapshot(&watched);
+        run(options, &mut previous);
+    }
+}
+
+/// Runs the days once in a child process and prints each part's result, noting any that differ
+/// from the previous run.
+fn run(options: &WatchOptions, previous: &mut HashMap<(String, String), RunSummary>) {
+    let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let mut command = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command
+        .args([
+            "run",
+            "--quiet",
+            "--profile",
+            &options.profile,
+            "--manifest-path",
+        ])
+        .arg(&manifest_path)
+        .args(["--", &options.day]);
+    let filter = &options.filter;
+    if let Some(input) = &filter.name {
+        command.arg(input);
+    }
+    // Every save would otherwise add a run to the history, drowning out deliberate ones.
+    command.args([
+        "--no-history",
+        "--format",
+        "json",
+        "--category",
+        &filter.category.to_string(),
+    ]);
+    for glob in filter.only.iter() {
+        command.args(["--only", glob]);
+    }
+    for glob in filter.skip.iter() {
+        command.args(["--skip", glob]);
+    }
+    for dir in options.inputs_dirs.iter() {
+        command.arg("--inputs-dir").arg(dir);
+    }
+    if let Some(timeout) = options.timeout {
+        command.args(["--timeout", &timeout.as_secs_f64().to_string()]);
+    }
+
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(e) => {
+            println!("Couldn't run cargo: {e}");
+            return;
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let runs = stdout.lines().filter_map(json::parse).collect::<Vec<_>>();
+    if runs.is_empty() {
+        // Nothing ran, so whatever went wrong, e.g. a compile error, is on stderr.
+        print!("{}", String::from_utf8_lossy(&output.stderr));
+        println!("Run failed ({})", output.status);
+        return;
+    }
+
+    let (mut passed, mut failed) = (0usize, 0usize);
+    for run in runs.iter() {
+        let field = |key: &str| run.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+        let key = (field("day").to_owned(), field("input").to_owned());
+        let summary = summarize(run);
+        let was = previous.get(&key);
+        let name = format!("{}/{}", key.0, key.1);
+
+        passed += summary.passed;
+        failed += summary.failed;
+        for (i, (status, actual)) in summary.parts.iter().enumerate() {
+            let now = format!("{status} {}", actual.as_deref().unwrap_or("-"));
+            let change = match was.map(|w| &w.parts[i]) {
+                None if previous.is_empty() => String::new(),
+                None => " (new)".to_owned(),
+                Some((s, a)) if s == status && a == actual => String::new(),
+                Some((s, a)) => format!(" (was {s} {})", a.as_deref().unwrap_or("-")),
+            };
+            println!("    [{name}] part {} {now}{change}", i + 1);
+        }
+        for problem in summary.problems.iter() {
+            println!("    [{name}] {problem}");
+        }
+        previous.insert(key, summary);
+    }
+    println!("{passed} passed, {failed} failed");
+}
+
+fn summarize(run: &json::Value) -> RunSummary {
+    let (mut passed, mut failed) = (0usize, 0usize);
+    let mut part = |key: &str| {
+        let p = run.get(key);
+        let field = |k: &str| p.and_then(|p| p.get(k)).and_then(|v| v.as_str());
+        let status = field("status").unwrap_or("?");
+        match status {
+            "PASS" => passed += 1,
+            "FAIL" => failed += 1,
+            "NO-RESULT" if field("expected").is_some() => failed += 1,
+            _ => {}
+        }
+        (status.to_owned(), field("actual").map(str::to_owned))
+    };
+    let parts = [part("part1"), part("part2")];
+    let crash = run.get("crash").and_then(|c| {
+        let message = c.get("message")?.as_str()?;
+        Some(match c.get("location").and_then(|l| l.as_str()) {
+            Some(location) => format!("CRASH panicked at {location}: {message}"),
+            None => format!("CRASH {message}"),
+        })
+    });
+    let error = run
+        .get("error")
+        .and_then(|e| e.as_str())
+        .map(|e| format!("ERROR {e}"));
+    let timeout = run
+        .get("timeout_ns")
+        .and_then(|t| t.as_u128())
+        .map(|ns| format!("TIMEOUT after {:#?}", Duration::from_nanos(ns as u64)));
+    // Each is a separate failure, in the same order as `RunReport::failures`.
+    let problems = [error, crash, timeout]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    failed += problems.len();
+    RunSummary {
+        parts,
+        passed,
+        failed,
+        problems,
+    }
+}
+
+/// Records the modification time and size of every file under `paths`.
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut pending = paths.to_vec();
+    while let Some(path) = pending.pop() {
+        let Ok(meta) = fs::metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            if let Ok(entries) = fs::read_dir(&path) {
+                pending.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()));
+            }
+        } else {
+            files.insert(path, (meta.modified().ok(), meta.len()));
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_each_problem_as_a_failure() {
+        let run = json::parse(concat!(
+            r#"{"day":"day01","input":"real","#,
+            r#""part1":{"status":"PASS","expected":"1","actual":"1"},"#,
+            r#""part2":{"status":"NO-RESULT","expected":"2","actual":null},"#,
+            r#""error":"bad input","timeout_ns":1000000}"#,
+        ))
+        .unwrap();
+        let summary = summarize(&run);
+        assert_eq!((summary.passed, summary.failed), (1, 3));
+        assert_eq!(summary.problems, ["ERROR bad input", "TIMEOUT after 1ms"]);
+    }
+}
@@ -0,0 +1,40 @@
+//! Checks how the command line rejects bad arguments, by running the built binary.
+
+use std::process::{Command, Output};
+
+fn aoc24(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc24"))
+        .args(args)
+        .env(
+            "AOC24_HISTORY",
+            std::env::temp_dir().join("aoc24-cli-test-history.jsonl"),
+        )
+        .output()
+        .unwrap()
+}
+
+fn assert_usage_error(args: &[&str]) {
+    let output = aoc24(args);
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "{args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn bench_budgets_must_be_positive() {
+    for budget in ["0", "0.0", "-1", "NaN"] {
+        assert_usage_error(&["bench", "day01", "--warmup", "0", "--time-budget", budget]);
+    }
+    assert_usage_error(&["bench", "day01", "--warmup", "0", "--iters", "0"]);
+}
+
+#[test]
+fn time_limits_must_be_positive() {
+    assert_usage_error(&["day01", "example", "--timeout", "0"]);
+    assert_usage_error(&["bench", "day01", "--timeout", "0"]);
+    assert_usage_error(&["watch", "day01", "--timeout", "0"]);
+    assert_usage_error(&["watch", "day01", "--interval", "0"]);
+}
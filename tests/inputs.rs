@@ -0,0 +1,83 @@
+//! Checks every solver against the expected answers in `inputs/`. The tests themselves are
+//! generated by `build.rs`, one per day, input and part, e.g. `day16_example2_part1`.
+
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use aoc24::{day_output::CancelToken, DayError, DayOutput, Params, Solution};
+
+/// How long a case's solver gets before it's cancelled, so a hung solver fails its tests instead
+/// of stalling the suite.
+const TIMEOUT: Duration = Duration::from_secs(300);
+/// The limit for a case with an ignored part. Only the other part is waited for, and that may
+/// come long before the ignored one finishes, e.g. day17's brute forced part 2.
+const ONE_PART_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn case_path(day: &str, case: &str, file: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "inputs", day, case, file]
+        .iter()
+        .collect()
+}
+
+fn solve(day: &str, case: &str) -> Solution {
+    let input = fs::read_to_string(case_path(day, case, "input.txt")).unwrap();
+    let case_dir = case_path(day, case, "");
+    let params = Params::load(&case_dir).unwrap_or_else(|e| panic!("{e}"));
+    let f = aoc24::solver(day).unwrap_or_else(|| panic!("no solver registered for {day}"));
+
+    let timeout =
+        match (1..=2).any(|p| case_path(day, case, &format!("ignore-part{p}.txt")).is_file()) {
+            true => ONE_PART_TIMEOUT,
+            false => TIMEOUT,
+        };
+    let cancel = CancelToken::default();
+    let token = cancel.clone();
+    let (done, finished) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        if finished.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+            token.cancel();
+        }
+    });
+
+    let mut output = DayOutput::with_cancel(cancel).with_params(params);
+    let error = f(&input, &mut output).err();
+    drop(done);
+    Solution { output, error }
+}
+
+/// Both parts of a case share one run of the solver. A part answered before the solver was
+/// cancelled is still checked.
+fn check(solution: &OnceLock<Solution>, day: &str, case: &str, part: usize) {
+    let solution = solution.get_or_init(|| solve(day, case));
+    let cancelled = matches!(solution.error, Some(DayError::Cancelled));
+    if let Some(e) = solution.error.as_ref().filter(|_| !cancelled) {
+        panic!("{day} input '{case}' failed: {e}");
+    }
+
+    let expected = fs::read_to_string(case_path(day, case, &format!("part{part}.txt"))).unwrap();
+    let actual = match part {
+        1 => solution.output.get_part1(),
+        _ => solution.output.get_part2(),
+    };
+    let actual = actual.unwrap_or_else(|| match cancelled {
+        true => panic!("{day} input '{case}' timed out before answering part {part}"),
+        false => panic!("{day} input '{case}' has no part {part} answer"),
+    });
+    if !actual.matches(&expected) {
+        assert_eq!(
+            actual.to_string(),
+            expected.trim(),
+            "{day} input '{case}' part {part}"
+        );
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/input_tests.rs"));